@@ -0,0 +1,24 @@
+use std::env;
+
+fn main() {
+    if env::var_os("CARGO_FEATURE_KTX2").is_some() {
+        link_cpp_runtime();
+    }
+}
+
+/// The prebuilt kernels of intel_tex_2 are C++ but the crate does not link the C++ runtime, pick the runtime the same
+/// way the `cc` crate does, `CXXSTDLIB` overrides it and an empty value links nothing.
+fn link_cpp_runtime() {
+    println!("cargo:rerun-if-env-changed=CXXSTDLIB");
+    let target = env::var("TARGET").unwrap_or_default();
+    let runtime = match env::var("CXXSTDLIB") {
+        Ok(s) => s,
+        Err(_) if target.contains("msvc") => String::new(),
+        Err(_) if target.contains("android") => "c++_shared".to_string(),
+        Err(_) if ["apple", "freebsd", "openbsd"].iter().any(|os| target.contains(os)) => "c++".to_string(),
+        Err(_) => "stdc++".to_string(),
+    };
+    if !runtime.is_empty() {
+        println!("cargo:rustc-link-lib={}", runtime);
+    }
+}
@@ -1,6 +1,7 @@
+use crate::traits::{io_error, open_image};
 use image::{GenericImageView, ImageResult, RgbaImage};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::{io::ErrorKind, path::Path};
 
 pub mod standard;
@@ -0,0 +1,86 @@
+use super::*;
+use std::time::Duration;
+
+/// A playback position in an [`AnimationFrame`], for fixed timestep loops that step the animation every tick.
+///
+/// Created by [`AnimationFrame::cursor`], the cursor only keeps the time within the loop, so stepping is a few integer
+/// operations no matter how long the game runs.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct AnimationCursor {
+    frame_ns: u128,
+    loop_ns: u128,
+    elapsed_ns: u128,
+}
+
+impl AnimationFrame {
+    /// Set how long each frame is shown, `0` means the animation stays at the first frame.
+    pub fn set_frame_duration(&mut self, duration: Duration) {
+        self.frame_ms = duration.as_millis().min(u32::MAX as u128) as u32;
+    }
+    /// Get how long each frame is shown.
+    pub fn get_frame_duration(&self) -> Duration {
+        Duration::from_millis(self.frame_ms as u64)
+    }
+    /// Get the index of the frame shown at the time point, the animation loops forever.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use tileset::{AnimationFrame, RgbaImage};
+    /// let mut animation =
+    ///     AnimationFrame::from_frames("water.png", vec![RgbaImage::new(16, 16); 4]).unwrap();
+    /// animation.set_frame_duration(Duration::from_millis(100));
+    /// assert_eq!(animation.frame_index_at(Duration::from_millis(250)), 2);
+    /// assert_eq!(animation.frame_index_at(Duration::from_millis(450)), 0);
+    /// ```
+    pub fn frame_index_at(&self, time: Duration) -> usize {
+        match (self.frame_ms, self.frames) {
+            (0, _) | (_, 0) => 0,
+            (ms, frames) => (time.as_millis() / ms as u128 % frames as u128) as usize,
+        }
+    }
+    /// Get the decoded frame shown at the time point, see [`AnimationFrame::frame_index_at`].
+    pub fn frame_at(&self, time: Duration) -> Option<&RgbaImage> {
+        self.get_frame(self.frame_index_at(time))
+    }
+    /// Create a cursor at the start of the animation.
+    pub fn cursor(&self) -> AnimationCursor {
+        let frame_ns = self.get_frame_duration().as_nanos();
+        AnimationCursor { frame_ns, loop_ns: frame_ns * self.frames as u128, elapsed_ns: 0 }
+    }
+}
+
+impl AnimationCursor {
+    /// Move the cursor forward and return the index of the frame shown, steps longer than the loop wrap around.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use tileset::{AnimationFrame, RgbaImage};
+    /// let mut animation =
+    ///     AnimationFrame::from_frames("water.png", vec![RgbaImage::new(16, 16); 4]).unwrap();
+    /// animation.set_frame_duration(Duration::from_millis(100));
+    /// let mut cursor = animation.cursor();
+    /// let dt = Duration::from_secs_f64(1.0 / 60.0);
+    /// let frame = cursor.advance(dt);
+    /// ```
+    pub fn advance(&mut self, dt: Duration) -> usize {
+        if self.loop_ns != 0 {
+            self.elapsed_ns = (self.elapsed_ns + dt.as_nanos() % self.loop_ns) % self.loop_ns;
+        }
+        self.index()
+    }
+    /// Get the index of the frame shown at the cursor.
+    pub fn index(&self) -> usize {
+        match self.frame_ns {
+            0 => 0,
+            ns => (self.elapsed_ns / ns) as usize,
+        }
+    }
+    /// Get the time since the start of the current loop.
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_ns as u64)
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+use image::imageops::replace;
+#[cfg(feature = "serde")]
+use serde::{de::Error, Deserializer, Serializer};
+
+/// An animation that stores only the changed region of each frame, see [`AnimationFrame::compress_delta`].
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AnimationDelta {
+    /// The first frame, stored in full
+    #[cfg_attr(feature = "serde", serde(with = "raw_image"))]
+    first: RgbaImage,
+    /// The changed region of each following frame relative to the previous frame, `None` if unchanged
+    patches: Vec<Option<DeltaPatch>>,
+}
+
+/// The pixels of a frame that changed, placed at `(x, y)`
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct DeltaPatch {
+    x: u32,
+    y: u32,
+    #[cfg_attr(feature = "serde", serde(with = "raw_image"))]
+    pixels: RgbaImage,
+}
+
+/// Images are stored as `(width, height, rgba bytes)`
+#[cfg(feature = "serde")]
+mod raw_image {
+    use super::*;
+
+    pub fn serialize<S>(image: &RgbaImage, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (image.width(), image.height(), image.as_raw()).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<RgbaImage, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (w, h, raw) = <(u32, u32, Vec<u8>)>::deserialize(deserializer)?;
+        RgbaImage::from_raw(w, h, raw).ok_or_else(|| D::Error::custom(format!("the pixels do not fill a {}x{} image", w, h)))
+    }
+}
+
+impl AnimationFrame {
+    /// Compress the frames by keeping only the bounding box of the pixels that changed since the previous frame,
+    /// returns `None` if the frames are not loaded.
+    ///
+    /// Animations where only a small part moves, such as ripples on a water tile, shrink to little more than one frame.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tileset::{AnimationFrame, RgbaImage};
+    /// let frames = vec![RgbaImage::new(16, 16), RgbaImage::new(16, 16)];
+    /// let animation = AnimationFrame::from_frames("water.png", frames).unwrap();
+    /// assert_eq!(animation.compress_delta().unwrap().decompress(), vec![RgbaImage::new(16, 16); 2]);
+    /// ```
+    pub fn compress_delta(&self) -> Option<AnimationDelta> {
+        let first = self.cache.first()?.clone();
+        let patches = self.cache.windows(2).map(|pair| changed_region(&pair[0], &pair[1])).collect();
+        Some(AnimationDelta { first, patches })
+    }
+}
+
+impl AnimationDelta {
+    /// Get the number of frames.
+    pub fn get_frame_count(&self) -> usize {
+        self.patches.len() + 1
+    }
+    /// Get the number of pixels actually stored, including the first frame.
+    pub fn stored_pixels(&self) -> usize {
+        let patches = self.patches.iter().flatten().map(|p| p.pixels.len() / 4).sum::<usize>();
+        self.first.len() / 4 + patches
+    }
+    /// Reconstruct all frames in full.
+    pub fn decompress(&self) -> Vec<RgbaImage> {
+        let mut frames = Vec::with_capacity(self.get_frame_count());
+        let mut current = self.first.clone();
+        frames.push(current.clone());
+        for patch in self.patches.iter() {
+            if let Some(patch) = patch {
+                replace(&mut current, &patch.pixels, patch.x as i64, patch.y as i64);
+            }
+            frames.push(current.clone());
+        }
+        frames
+    }
+}
+
+fn changed_region(old: &RgbaImage, new: &RgbaImage) -> Option<DeltaPatch> {
+    let mut changed = old.enumerate_pixels().zip(new.pixels()).filter(|((_, _, a), b)| a != b).map(|((x, y, _), _)| (x, y));
+    let (x, y) = changed.next()?;
+    let (min_x, min_y, max_x, max_y) =
+        changed.fold((x, y, x, y), |(x0, y0, x1, y1), (x, y)| (x0.min(x), y0.min(y), x1.max(x), y1.max(y)));
+    let pixels = new.view(min_x, min_y, max_x - min_x + 1, max_y - min_y + 1).to_image();
+    Some(DeltaPatch { x: min_x, y: min_y, pixels })
+}
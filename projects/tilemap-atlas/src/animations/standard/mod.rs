@@ -1,6 +1,10 @@
 use super::*;
 use std::path::PathBuf;
 
+mod cursor;
+mod delta;
+pub use self::{cursor::AnimationCursor, delta::AnimationDelta};
+
 /// Combining multiple sequence frame sprites into one animation frame sprites
 ///
 /// # Arguments
@@ -20,13 +24,19 @@ use std::path::PathBuf;
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AnimationFrame {
     /// describe how to get the sprites, the name in memory, the relative path name in disk
-    key: String,
+    pub(crate) key: String,
     /// The width of the cell in pixels
     cell_w: u32,
     /// The height of the cell in pixels
     cell_h: u32,
     /// The number of sprites
     frames: usize,
+    /// How long each sprite is shown in milliseconds, `0` means the animation is not played
+    #[cfg_attr(feature = "serde", serde(default))]
+    frame_ms: u32,
+    /// The decoded sprites
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cache: Vec<RgbaImage>,
 }
 
 impl AnimationFrame {
@@ -52,16 +62,36 @@ impl AnimationFrame {
     {
         let folder = folder.as_ref().canonicalize()?;
         let first = names.first().expect("The names slice must not be empty").as_ref();
-        let (cell_w, cell_h) = image::open(folder.join(first))?.dimensions();
+        let (cell_w, cell_h) = open_image(folder.join(first))?.dimensions();
         let mut output = RgbaImage::new(cell_w, cell_h * names.len() as u32);
         for (i, file) in names.iter().enumerate() {
-            let image = image::open(folder.join(file.as_ref()))?.to_rgba8();
+            let image = open_image(folder.join(file.as_ref()))?;
             let y = i as i64 * cell_h as i64;
             image::imageops::overlay(&mut output, &image, 0, y);
         }
         let file_name = format!("{}.png", target);
         output.save(folder.join(&file_name))?;
-        Ok(AnimationFrame { cell_w, cell_h, frames: names.len(), key: file_name })
+        let cache = (0..names.len() as u32).map(|i| output.view(0, i * cell_h, cell_w, cell_h).to_image()).collect();
+        Ok(AnimationFrame { cell_w, cell_h, frames: names.len(), frame_ms: 0, key: file_name, cache })
+    }
+    /// Create an animation from in-memory frames, all frames must have the same size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use tileset::{AnimationFrame, RgbaImage};
+    /// let frames = vec![RgbaImage::new(16, 16), RgbaImage::new(16, 16)];
+    /// let animation = AnimationFrame::from_frames("water.png", frames).unwrap();
+    /// ```
+    pub fn from_frames<S>(key: S, frames: Vec<RgbaImage>) -> ImageResult<AnimationFrame>
+    where
+        S: ToString,
+    {
+        let (cell_w, cell_h) = frames.first().map(|f| f.dimensions()).unwrap_or_default();
+        if frames.iter().any(|f| f.dimensions() != (cell_w, cell_h)) {
+            io_error("All frames of the animation must have the same size", ErrorKind::InvalidInput)?;
+        }
+        Ok(AnimationFrame { key: key.to_string(), cell_w, cell_h, frames: frames.len(), frame_ms: 0, cache: frames })
     }
 }
 
@@ -76,6 +106,10 @@ impl AnimationFrame {
     pub fn get_key(&self) -> &str {
         &self.key
     }
+    /// Get the width and height of a single cell.
+    pub fn get_cell_size(&self) -> (u32, u32) {
+        (self.cell_w, self.cell_h)
+    }
     /// Get the image path if it is a disk image
     ///
     /// # Examples
@@ -86,4 +120,20 @@ impl AnimationFrame {
     pub fn get_path(&self, root: &Path) -> PathBuf {
         root.join(&self.key)
     }
+    /// Get the decoded frame by index, returns `None` if the index is out of range or the frames are not loaded.
+    pub fn get_frame(&self, index: usize) -> Option<&RgbaImage> {
+        self.cache.get(index)
+    }
+    /// Stack the decoded frames vertically like [`AnimationFrame::composite_sequence_frames`], `None` if the frames are
+    /// not loaded.
+    pub(crate) fn to_sheet(&self) -> Option<RgbaImage> {
+        if self.cache.is_empty() {
+            return None;
+        }
+        let mut output = RgbaImage::new(self.cell_w, self.cell_h * self.cache.len() as u32);
+        for (i, frame) in self.cache.iter().enumerate() {
+            image::imageops::replace(&mut output, frame, 0, (i as u32 * self.cell_h) as i64);
+        }
+        Some(output)
+    }
 }
@@ -43,6 +43,22 @@ impl FileSystemTiles {
             }
         }
     }
+    /// Load the workspace and decode all referenced images up front, so later lookups do not touch the disk.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::FileSystemTiles;
+    /// let tiles = FileSystemTiles::open("assets/tile-set-1/").unwrap();
+    /// ```
+    pub fn open<S>(workspace: S) -> ImageResult<Self>
+    where
+        S: AsRef<Path>,
+    {
+        let out = Self::load(workspace)?;
+        out.decode_serial()?;
+        Ok(out)
+    }
 }
 
 struct VisitorFileSystemTiles<'i> {
@@ -79,7 +95,18 @@ impl<'i, 'de> Visitor<'de> for VisitorFileSystemTiles<'i> {
     {
         while let Some(key) = map.next_key::<String>()? {
             match key.as_str() {
-                // "size" => self.ptr.target_w = map.next_value()?,
+                "target_size" => {
+                    let (w, h) = map.next_value::<(NonZeroU32, NonZeroU32)>()?;
+                    self.ptr.target_w = w;
+                    self.ptr.target_h = h;
+                }
+                "attribution" => self.ptr.attribution = map.next_value()?,
+                "license" => self.ptr.license = map.next_value()?,
+                "atlas" => {
+                    for (name, data) in map.next_value::<Vec<(String, TileAtlasData)>>()? {
+                        self.ptr.atlas.insert(name, data);
+                    }
+                }
                 _ => {
                     map.next_value::<serde_json::Value>()?;
                 }
@@ -0,0 +1,45 @@
+use super::*;
+use std::io::Read;
+
+impl FileSystemTiles {
+    /// Open a read only workspace hosted over http, `TileSet.json5` and all referenced images are fetched up front and
+    /// kept in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::FileSystemTiles;
+    /// let tiles = FileSystemTiles::open_url("https://example.com/tile-set-1/").unwrap();
+    /// ```
+    pub fn open_url(base_url: &str) -> ImageResult<Self> {
+        let base = base_url.trim_end_matches('/');
+        let json = fetch_bytes(&format!("{}/TileSet.json5", base))?;
+        let mut out = Self { remote: Some(base.to_string()), ..Default::default() };
+        let mut der = serde_json::Deserializer::from_slice(&json);
+        if let Err(e) = FileSystemTiles::deserialize_in_place(&mut der, &mut out) {
+            io_error(
+                format!("The file {}/TileSet.json5 is not a valid TileSet.json5 file: {}", base, e),
+                ErrorKind::InvalidData,
+            )?
+        }
+        for item in out.atlas.iter() {
+            let key = item.value().get_name();
+            if !out.fetched.contains_key(key) {
+                out.fetched.insert(key.to_string(), fetch_bytes(&format!("{}/{}", base, key))?);
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn fetch_bytes(url: &str) -> ImageResult<Vec<u8>> {
+    let response = match ureq::get(url).call() {
+        Ok(o) => o,
+        Err(e) => io_error(format!("Failed to fetch {}: {}", url, e), ErrorKind::Other)?,
+    };
+    let mut bytes = vec![];
+    if let Err(e) = response.into_reader().read_to_end(&mut bytes) {
+        io_error(format!("Failed to read {}: {}", url, e), e.kind())?
+    }
+    Ok(bytes)
+}
@@ -1,10 +1,11 @@
 use crate::{
-    traits::io_error, AnimationFrame, GridCornerAtlas, GridCornerWang, GridEdgeAtlas, GridEdgeWang, GridSimpleAtlas,
-    TilesProvider,
+    grids::corner_set::integer_scale,
+    traits::{io_error, open_image},
+    AnimationFrame, GridCornerAtlas, GridCornerWang, GridEdgeAtlas, GridEdgeWang, GridSimpleAtlas, TilesProvider,
 };
 
-use crate::utils::grid_corner_mask;
-use dashmap::DashMap;
+use crate::utils::{grid_corner_mask, save_as_png};
+use dashmap::{mapref::entry::Entry, DashMap};
 use image::{ImageResult, RgbaImage};
 use serde::{Deserialize, Serialize};
 use serde_json::ser::PrettyFormatter;
@@ -12,10 +13,16 @@ use std::{
     fs::{create_dir_all, File},
     io::ErrorKind,
     num::NonZeroU32,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 mod der;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod ser;
 
 impl TilesProvider for FileSystemTiles {}
@@ -26,6 +33,19 @@ pub struct FileSystemTiles {
     target_w: NonZeroU32,
     target_h: NonZeroU32,
     atlas: DashMap<String, TileAtlasData>,
+    /// The base url if the workspace is fetched over http, remote workspaces are read only
+    remote: Option<String>,
+    /// The fetched image bytes of a remote workspace
+    fetched: DashMap<String, Vec<u8>>,
+    /// The credit line required by the creators of the tile set
+    attribution: Option<String>,
+    /// The license of the tile set, such as an SPDX identifier
+    license: Option<String>,
+    /// The images decoded up front by [`FileSystemTiles::open`] or [`FileSystemTiles::warmup`]
+    decoded: DashMap<String, Arc<RgbaImage>>,
+    /// The corner cells cropped by [`FileSystemTiles::get_corner`] or [`FileSystemTiles::warmup`], keyed by the atlas
+    /// name, the mask and the variant
+    cells: DashMap<(String, u8, u8), Arc<RgbaImage>>,
 }
 
 impl Default for FileSystemTiles {
@@ -36,6 +56,12 @@ impl Default for FileSystemTiles {
                 target_w: NonZeroU32::new_unchecked(32),
                 target_h: NonZeroU32::new_unchecked(32),
                 atlas: Default::default(),
+                remote: None,
+                fetched: Default::default(),
+                attribution: None,
+                license: None,
+                decoded: Default::default(),
+                cells: Default::default(),
             }
         }
     }
@@ -43,6 +69,9 @@ impl Default for FileSystemTiles {
 
 impl FileSystemTiles {
     fn write_json(&self) -> ImageResult<()> {
+        if let Some(url) = &self.remote {
+            io_error(format!("The remote workspace {} is read only", url), ErrorKind::Unsupported)?
+        }
         let path = File::create(self.workspace.join("TileSet.json5"))?;
         let mut pretty = serde_json::Serializer::with_formatter(path, PrettyFormatter::with_indent(b"    "));
         match self.serialize(&mut pretty) {
@@ -67,28 +96,283 @@ impl FileSystemTiles {
         }
         self.write_json()
     }
+    /// Get the credit line required by the creators of the tile set.
+    pub fn get_attribution(&self) -> Option<&str> {
+        self.attribution.as_deref()
+    }
+    /// Set the credit line of the tile set, `None` removes it from `TileSet.json5`.
+    pub fn set_attribution(&mut self, attribution: Option<&str>) -> ImageResult<()> {
+        self.attribution = attribution.map(|s| s.to_string());
+        self.write_json()
+    }
+    /// Get the license of the tile set.
+    pub fn get_license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+    /// Set the license of the tile set, such as an SPDX identifier like `CC-BY-4.0`, `None` removes it from
+    /// `TileSet.json5`.
+    pub fn set_license(&mut self, license: Option<&str>) -> ImageResult<()> {
+        self.license = license.map(|s| s.to_string());
+        self.write_json()
+    }
+    /// Get the image by key, from the fetched bytes of a remote workspace or from the workspace directory.
+    ///
+    /// Decoded images are shared, getting them again does not copy the pixels.
+    pub fn get_image(&self, key: &str) -> ImageResult<Arc<RgbaImage>> {
+        if let Some(image) = self.decoded.get(key) {
+            return Ok(image.value().clone());
+        }
+        Ok(Arc::new(self.read_image(key)?))
+    }
+    /// Decode every referenced image that is not decoded yet and crop every cell of the corner atlases, so that later
+    /// calls to [`FileSystemTiles::get_image`] and [`FileSystemTiles::get_corner`] never touch the disk or crop a cell
+    /// again, returns the time spent.
+    ///
+    /// The images are decoded on the rayon thread pool if the `rayon` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::FileSystemTiles;
+    /// let tiles = FileSystemTiles::load("assets/tile-set-1/").unwrap();
+    /// let elapsed = tiles.warmup().unwrap();
+    /// println!("decoded in {:?}", elapsed);
+    /// ```
+    pub fn warmup(&self) -> ImageResult<Duration> {
+        let start = Instant::now();
+        #[cfg(feature = "rayon")]
+        self.decode_parallel()?;
+        #[cfg(not(feature = "rayon"))]
+        self.decode_serial()?;
+        self.crop_cells()?;
+        Ok(start.elapsed())
+    }
+    /// Check if the image is already decoded and will be served from memory.
+    pub fn is_decoded(&self, key: &str) -> bool {
+        self.decoded.contains_key(key)
+    }
+    /// Check if the corner cell is already cropped and will be served from memory, wang sheets only have the variant
+    /// `0`.
+    pub fn is_cell_cached(&self, name: &str, mask: u8, index: u8) -> bool {
+        self.cells.contains_key(&(name.to_string(), mask, index))
+    }
+    fn read_image(&self, key: &str) -> ImageResult<RgbaImage> {
+        match self.fetched.get(key) {
+            Some(bytes) => Ok(image::load_from_memory(bytes.value())?.to_rgba8()),
+            None => open_image(self.workspace.join(key)),
+        }
+    }
+    fn decode_serial(&self) -> ImageResult<()> {
+        for key in self.image_keys() {
+            if !self.decoded.contains_key(&key) {
+                let image = self.read_image(&key)?;
+                self.decoded.insert(key, Arc::new(image));
+            }
+        }
+        Ok(())
+    }
     pub fn get_atlas(&self, name: &str, _mask: u8) -> Option<TileAtlasData> {
         self.atlas.get(name).map(|a| a.value().clone())
     }
+    /// Get a corner cell, the cell is cropped from the image on first use and served from memory afterwards.
     pub fn get_corner(&self, name: &str, lu: bool, ru: bool, ld: bool, rd: bool, index: u8) -> Option<RgbaImage> {
-        let mask = grid_corner_mask(lu, ru, ld, rd);
-        match self.atlas.get(name)?.value() {
+        let cell = self.corner_cell(name, grid_corner_mask(lu, ru, ld, rd), index)?;
+        Some(cell.as_ref().clone())
+    }
+    fn corner_cell(&self, name: &str, mask: u8, index: u8) -> Option<Arc<RgbaImage>> {
+        let atlas = self.atlas.get(name)?;
+        // wang sheets have a single variant
+        let index = match atlas.value() {
+            TileAtlasData::GridCornerWang(_) => 0,
+            _ => index,
+        };
+        let slot = (name.to_string(), mask, index);
+        if let Some(cell) = self.cells.get(&slot) {
+            return Some(cell.value().clone());
+        }
+        let cell = match atlas.value() {
             TileAtlasData::SimpleSet(_) => None,
             TileAtlasData::Animation(_) => None,
-            TileAtlasData::GridCorner(v) => v.load_corner(&self.workspace, mask as u32, index as u32).ok(),
-            TileAtlasData::GridCornerWang(v) => v.load_corner(&self.workspace, mask).ok(),
+            TileAtlasData::GridCorner(v) => {
+                let sheet = self.get_image(v.get_key()).ok()?;
+                v.view_corner(&sheet, mask as u32, index as u32).ok()
+            }
+            TileAtlasData::GridCornerWang(v) => {
+                let sheet = self.get_image(v.get_key()).ok()?;
+                Some(v.view_corner(&sheet, mask))
+            }
             TileAtlasData::GridEdge(_) => None,
             TileAtlasData::GridEdgeWang(_) => None,
+        };
+        let cell = Arc::new(cell?);
+        // the atlas is still borrowed, so a concurrent replace clears the cell after it is cached
+        self.cells.insert(slot, cell.clone());
+        Some(cell)
+    }
+    fn crop_cells(&self) -> ImageResult<()> {
+        let mut slots = vec![];
+        for item in self.atlas.iter() {
+            let counts = match item.value() {
+                TileAtlasData::GridCorner(v) => (0..16).map(|mask| v.get_count(mask)).collect(),
+                TileAtlasData::GridCornerWang(_) => vec![1; 16],
+                _ => continue,
+            };
+            for (mask, count) in counts.into_iter().enumerate() {
+                slots.extend((0..count).map(|index| (item.key().clone(), mask as u8, index)));
+            }
+        }
+        for (name, mask, index) in slots {
+            if self.corner_cell(&name, mask, index).is_none() {
+                io_error(
+                    format!("The variant {} of mask 0b{:04b} is out of the image of atlas {}", index, mask, name),
+                    ErrorKind::InvalidData,
+                )?
+            }
         }
+        Ok(())
+    }
+    /// Drop the cropped cells of the atlas, after the atlas was replaced or renamed.
+    fn forget_cells(&self, name: &str) {
+        self.cells.retain(|(atlas, _, _), _| atlas != name);
     }
     pub fn get_side_atlas(&self, file: &str, _mask: u8) -> Option<TileAtlasData> {
         self.atlas.get(file).map(|a| a.value().clone())
     }
-    pub fn insert_atlas(&self, file: &str, data: TileAtlasData) -> ImageResult<()> {
+    /// The keys of all images referenced by the atlases, sorted and without duplicates.
+    fn image_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.atlas.iter().map(|item| item.value().get_name().to_string()).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        keys
+    }
+    /// Run the checks of [`FileSystemTiles::insert_atlas`] without inserting the atlas or writing the json file.
+    ///
+    /// A taken name passes, just like inserting replaces the existing atlas, check [`FileSystemTiles::get_atlas`] first
+    /// to refuse replacing it.
+    pub fn validate_insert(&self, file: &str, data: &TileAtlasData) -> ImageResult<()> {
+        self.check_atlas(file, data)
+    }
+    /// The atlas is rejected if the workspace is remote, the name leaves the workspace, the cell size is zero, or the
+    /// cells can not be scaled to the target size by an integer factor.
+    fn check_atlas(&self, file: &str, data: &TileAtlasData) -> ImageResult<()> {
+        if let Some(url) = &self.remote {
+            io_error(format!("The remote workspace {} is read only", url), ErrorKind::Unsupported)?
+        }
+        check_name(file)?;
+        let (w, h) = data.get_cell_size();
+        if w == 0 || h == 0 {
+            io_error(format!("The cell size of atlas {} must be greater than zero", file), ErrorKind::InvalidInput)?
+        }
+        let (target_w, target_h) = self.get_target_size();
+        let scaled = integer_scale(w, h, target_w).map(|s| {
+            if target_w >= w {
+                h * s
+            }
+            else {
+                h / s
+            }
+        });
+        if scaled.ok() != Some(target_h) {
+            io_error(
+                format!("The cell size {}x{} of atlas {} can not be scaled to {}x{}", w, h, file, target_w, target_h),
+                ErrorKind::InvalidInput,
+            )?
+        }
+        Ok(())
+    }
+    /// Insert the atlas and update the json file, an atlas with the same name is replaced.
+    ///
+    /// If the atlas carries decoded cells, they are written to `<file>.png` in the workspace and the atlas key is pointed
+    /// at that image, atlases that only reference an image on disk are stored as is.
+    ///
+    /// ## Breaking
+    ///
+    /// Atlases with a zero cell size, or cells that can not be scaled to the target size by an integer factor, are now
+    /// rejected, they used to be stored as is. Use [`FileSystemTiles::validate_insert`] to check an atlas beforehand.
+    pub fn insert_atlas(&self, file: &str, mut data: TileAtlasData) -> ImageResult<()> {
+        self.check_atlas(file, &data)?;
+        if let Some(image) = data.to_sheet() {
+            let key = format!("{}.png", file);
+            let path = self.workspace.join(&key);
+            if let Some(parent) = path.parent() {
+                create_dir_all(parent)?;
+            }
+            save_as_png(&image, &path)?;
+            // the image of a replaced atlas may be decoded already
+            self.decoded.remove(&key);
+            data.set_key(key);
+        }
         self.atlas.insert(file.to_string(), data);
+        self.forget_cells(file);
         self.write_json()?;
         Ok(())
     }
+    /// Rename the atlas, the image written by [`FileSystemTiles::insert_atlas`] is renamed along with it.
+    ///
+    /// The new name is claimed before the old one is released, so readers always find the atlas under one of the names
+    /// and a concurrent insert under the new name is never overwritten. An existing image at the new path is never
+    /// replaced, and the rename is undone if the `TileSet.json5` file can not be written, so the workspace always
+    /// matches the manifest.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::FileSystemTiles;
+    /// let tiles = FileSystemTiles::load("assets/tile-set-1/").unwrap();
+    /// tiles.rename("grass", "meadow").unwrap();
+    /// ```
+    pub fn rename(&self, old: &str, new: &str) -> ImageResult<()> {
+        if let Some(url) = &self.remote {
+            io_error(format!("The remote workspace {} is read only", url), ErrorKind::Unsupported)?
+        }
+        check_name(new)?;
+        let Some(mut data) = self.atlas.get(old).map(|a| a.value().clone())
+        else {
+            return io_error(format!("The atlas {} does not exist", old), ErrorKind::NotFound);
+        };
+        let (old_key, new_key) = (format!("{}.png", old), format!("{}.png", new));
+        let owned = data.get_name() == old_key;
+        // the entry holds the lock of the new name until the atlas is inserted
+        match self.atlas.entry(new.to_string()) {
+            Entry::Occupied(_) => io_error(format!("The atlas {} already exists", new), ErrorKind::AlreadyExists)?,
+            Entry::Vacant(entry) => {
+                if owned {
+                    self.move_image(&old_key, &new_key)?;
+                    data.set_key(new_key.clone());
+                }
+                entry.insert(data);
+            }
+        }
+        let removed = self.atlas.remove(old);
+        self.forget_cells(old);
+        if let Err(e) = self.write_json() {
+            self.atlas.remove(new);
+            self.forget_cells(new);
+            if owned {
+                self.move_image(&new_key, &old_key).ok();
+            }
+            if let Some((_, data)) = removed {
+                self.atlas.insert(old.to_string(), data);
+            }
+            return Err(e);
+        }
+        Ok(())
+    }
+    /// Move an image in the workspace, an existing image at the target is never replaced.
+    fn move_image(&self, from: &str, to: &str) -> ImageResult<()> {
+        let target = self.workspace.join(to);
+        if target.exists() {
+            io_error(format!("The image {} already exists", target.display()), ErrorKind::AlreadyExists)?
+        }
+        if let Some(parent) = target.parent() {
+            create_dir_all(parent)?;
+        }
+        std::fs::rename(self.workspace.join(from), target)?;
+        if let Some((_, image)) = self.decoded.remove(from) {
+            self.decoded.insert(to.to_string(), image);
+        }
+        Ok(())
+    }
     pub fn update_atlas(&self, file: &str) -> ImageResult<()> {
         match self.atlas.get(file) {
             Some(_) => {
@@ -130,4 +414,40 @@ impl TileAtlasData {
             TileAtlasData::GridEdgeWang(v) => v.get_key(),
         }
     }
+    /// Render the decoded cells in the layout the atlas reads from disk, `None` if the atlas only references an image.
+    fn to_sheet(&self) -> Option<RgbaImage> {
+        match self {
+            TileAtlasData::SimpleSet(v) => v.to_sheet(),
+            TileAtlasData::Animation(v) => v.to_sheet(),
+            TileAtlasData::GridCorner(v) if !v.cache.is_empty() => Some(v.to_image()),
+            _ => None,
+        }
+    }
+    fn set_key(&mut self, key: String) {
+        match self {
+            TileAtlasData::SimpleSet(v) => v.key = key,
+            TileAtlasData::Animation(v) => v.key = key,
+            TileAtlasData::GridCorner(v) => v.key = key,
+            _ => {}
+        }
+    }
+    /// Get the width and height of a single cell.
+    pub fn get_cell_size(&self) -> (u32, u32) {
+        match self {
+            TileAtlasData::SimpleSet(v) => v.get_cell_size(),
+            TileAtlasData::Animation(v) => v.get_cell_size(),
+            TileAtlasData::GridCorner(v) => v.get_cell_size(),
+            TileAtlasData::GridCornerWang(v) => v.get_cell_size(),
+            TileAtlasData::GridEdge(v) => v.get_cell_size(),
+            TileAtlasData::GridEdgeWang(v) => v.get_cell_size(),
+        }
+    }
+}
+
+/// The image of an atlas is written to `<name>.png`, so the name must be a relative path that stays in the workspace.
+fn check_name(file: &str) -> ImageResult<()> {
+    if !Path::new(file).components().all(|c| matches!(c, Component::Normal(_))) {
+        io_error(format!("The atlas name {:?} must be a relative path inside the workspace", file), ErrorKind::InvalidInput)?
+    }
+    Ok(())
 }
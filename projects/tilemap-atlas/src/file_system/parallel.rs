@@ -0,0 +1,41 @@
+use super::*;
+use rayon::prelude::*;
+
+impl FileSystemTiles {
+    /// Same as [`FileSystemTiles::open`], but the images are decoded on the rayon thread pool.
+    ///
+    /// If several images fail to decode, the error of the first failing key in sorted order is returned, the error
+    /// message contains the path of the file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::FileSystemTiles;
+    /// let tiles = FileSystemTiles::open_parallel("assets/tile-set-1/").unwrap();
+    /// ```
+    pub fn open_parallel<S>(workspace: S) -> ImageResult<Self>
+    where
+        S: AsRef<Path>,
+    {
+        let out = Self::load(workspace)?;
+        out.decode_parallel()?;
+        Ok(out)
+    }
+    pub(super) fn decode_parallel(&self) -> ImageResult<()> {
+        let keys: Vec<String> = self.image_keys().into_iter().filter(|key| !self.decoded.contains_key(key)).collect();
+        let first_error = keys
+            .par_iter()
+            .filter_map(|key| match self.read_image(key) {
+                Ok(image) => {
+                    self.decoded.insert(key.clone(), Arc::new(image));
+                    None
+                }
+                Err(e) => Some((key, e)),
+            })
+            .min_by(|a, b| a.0.cmp(b.0));
+        match first_error {
+            Some((_, e)) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
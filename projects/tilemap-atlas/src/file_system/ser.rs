@@ -13,8 +13,14 @@ impl Serialize for FileSystemTiles {
             .sorted_unstable_by(|a, b| a.key().cmp(b.key()))
             .map(|m| (m.key().clone(), m.value().clone()))
             .collect::<Vec<_>>();
-        let mut state = serializer.serialize_struct("FileSystemTiles", 3)?;
+        let mut state = serializer.serialize_struct("FileSystemTiles", 4)?;
         state.serialize_field("target_size", &(self.target_w, self.target_h))?;
+        if let Some(attribution) = &self.attribution {
+            state.serialize_field("attribution", attribution)?;
+        }
+        if let Some(license) = &self.license {
+            state.serialize_field("license", license)?;
+        }
         state.serialize_field("atlas", &items)?;
         state.end()
     }
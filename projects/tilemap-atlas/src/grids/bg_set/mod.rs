@@ -1,17 +1,71 @@
 use super::*;
+use crate::grids::corner_set::pixels;
+use std::cmp::Ordering;
 
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg(feature = "serde")]
+mod texture_packer;
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GridSimpleAtlas {
-    key: String,
+    pub(crate) key: String,
     cell_w: u32,
     cell_h: u32,
     grid_w: u32,
     grid_h: u32,
+    /// The name of each cell, in row-major order
+    #[cfg_attr(feature = "serde", serde(default))]
+    names: Vec<String>,
+    /// The decoded cells, in row-major order
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cache: Vec<RgbaImage>,
+}
+
+/// Atlases are ordered by key, cell size and grid size, the names and the decoded cells only break ties
+impl Ord for GridSimpleAtlas {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.key, self.cell_w, self.cell_h, self.grid_w, self.grid_h)
+            .cmp(&(&other.key, other.cell_w, other.cell_h, other.grid_w, other.grid_h))
+            .then_with(|| self.names.cmp(&other.names))
+            .then_with(|| self.cache.iter().map(pixels).cmp(other.cache.iter().map(pixels)))
+    }
+}
+
+impl PartialOrd for GridSimpleAtlas {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl GridSimpleAtlas {
     pub fn get_key(&self) -> &str {
         &self.key
     }
+    /// Get the width and height of a single cell.
+    pub fn get_cell_size(&self) -> (u32, u32) {
+        (self.cell_w, self.cell_h)
+    }
+    /// Get the names of all cells, in row-major order.
+    pub fn get_names(&self) -> &[String] {
+        &self.names
+    }
+    /// Get the cell by name, returns `None` if the name does not exist or the cells are not loaded.
+    pub fn get_cell(&self, name: &str) -> Option<&RgbaImage> {
+        let index = self.names.iter().position(|n| n == name)?;
+        self.cache.get(index)
+    }
+    /// Pack the decoded cells back into a sheet, `None` if the cells are not loaded.
+    pub(crate) fn to_sheet(&self) -> Option<RgbaImage> {
+        let cols = self.grid_w.max(1);
+        if self.cache.is_empty() {
+            return None;
+        }
+        let rows = (self.cache.len() as u32).div_ceil(cols);
+        let mut output = RgbaImage::new(cols * self.cell_w, rows * self.cell_h);
+        for (i, cell) in self.cache.iter().enumerate() {
+            let (x, y) = (i as u32 % cols * self.cell_w, i as u32 / cols * self.cell_h);
+            image::imageops::replace(&mut output, cell, x as i64, y as i64);
+        }
+        Some(output)
+    }
 }
@@ -0,0 +1,113 @@
+use super::*;
+use crate::traits::io_error;
+use image::imageops::{overlay, rotate270};
+use std::{collections::BTreeMap, io::ErrorKind};
+
+#[derive(Deserialize)]
+struct TexturePackerSheet {
+    frames: TexturePackerFrames,
+}
+
+/// TexturePacker exports the frames either as `JSON (Hash)` or `JSON (Array)`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TexturePackerFrames {
+    Hash(BTreeMap<String, TexturePackerFrame>),
+    Array(Vec<TexturePackerFrame>),
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TexturePackerFrame {
+    #[serde(default)]
+    filename: String,
+    frame: TexturePackerRect,
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    trimmed: bool,
+    sprite_source_size: Option<TexturePackerRect>,
+    source_size: Option<TexturePackerSize>,
+}
+
+#[derive(Deserialize)]
+struct TexturePackerRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct TexturePackerSize {
+    w: u32,
+    h: u32,
+}
+
+impl TexturePackerFrame {
+    /// Cut the frame out of the sheet, then undo the rotation and trimming.
+    fn extract(&self, image: &RgbaImage) -> ImageResult<RgbaImage> {
+        let rect = &self.frame;
+        // rotated frames are stored 90° clockwise, `w` and `h` still describe the unrotated sprite
+        let (w, h) = if self.rotated { (rect.h, rect.w) } else { (rect.w, rect.h) };
+        if rect.x + w > image.width() || rect.y + h > image.height() {
+            io_error(format!("The frame {:?} is out of the image range", self.filename), ErrorKind::InvalidInput)?;
+        }
+        let view = image.view(rect.x, rect.y, w, h).to_image();
+        let sprite = if self.rotated { rotate270(&view) } else { view };
+        match (self.trimmed, &self.sprite_source_size, &self.source_size) {
+            (true, Some(offset), Some(size)) => {
+                let mut output = RgbaImage::new(size.w, size.h);
+                overlay(&mut output, &sprite, offset.x as i64, offset.y as i64);
+                Ok(output)
+            }
+            _ => Ok(sprite),
+        }
+    }
+}
+
+impl GridSimpleAtlas {
+    /// Import a sheet packed by [TexturePacker](https://www.codeandweb.com/texturepacker), both `JSON (Hash)` and
+    /// `JSON (Array)` formats are supported.
+    ///
+    /// Rotated frames are rotated back and trimmed frames are restored to their source size, all frames must share the
+    /// same source size. The cells are laid out in a single row, hash frames are ordered by name.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridSimpleAtlas;
+    /// let json = std::fs::read_to_string("assets/sheet.json").unwrap();
+    /// let image = image::open("assets/sheet.png").unwrap().to_rgba8();
+    /// let atlas = GridSimpleAtlas::from_texturepacker(&json, &image).unwrap();
+    /// ```
+    pub fn from_texturepacker(json: &str, image: &RgbaImage) -> ImageResult<Self> {
+        let sheet: TexturePackerSheet = match serde_json::from_str(json) {
+            Ok(o) => o,
+            Err(e) => io_error(format!("The json is not a valid TexturePacker sheet: {}", e), ErrorKind::InvalidData)?,
+        };
+        let frames = match sheet.frames {
+            TexturePackerFrames::Hash(map) => {
+                map.into_iter().map(|(filename, frame)| TexturePackerFrame { filename, ..frame }).collect()
+            }
+            TexturePackerFrames::Array(list) => list,
+        };
+        let mut names = Vec::with_capacity(frames.len());
+        let mut cache: Vec<RgbaImage> = Vec::with_capacity(frames.len());
+        for frame in frames {
+            let cell = frame.extract(image)?;
+            if let Some(first) = cache.first() {
+                if first.dimensions() != cell.dimensions() {
+                    io_error(
+                        format!("The frame {:?} does not have the same size as the other frames", frame.filename),
+                        ErrorKind::InvalidInput,
+                    )?;
+                }
+            }
+            names.push(frame.filename);
+            cache.push(cell);
+        }
+        let (cell_w, cell_h) = cache.first().map(|c| c.dimensions()).unwrap_or_default();
+        Ok(Self { key: String::new(), cell_w, cell_h, grid_w: cache.len() as u32, grid_h: 1, names, cache })
+    }
+}
@@ -65,7 +65,7 @@ impl GridCompleteAtlas {
     where
         P: AsRef<Path>,
     {
-        Self::new(image::open(path)?.to_rgba8())
+        Self::new(open_image(path)?)
     }
     /// Save the tile set image to a png file, remember you need add `.png` suffix.
     ///
@@ -83,3 +83,10 @@ impl GridCompleteAtlas {
         save_as_png(&self.image, path)
     }
 }
+
+/// Borrow the source image of the tile set.
+impl AsRef<RgbaImage> for GridCompleteAtlas {
+    fn as_ref(&self) -> &RgbaImage {
+        &self.image
+    }
+}
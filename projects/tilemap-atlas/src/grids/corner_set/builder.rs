@@ -0,0 +1,97 @@
+use super::*;
+
+/// Assemble a [`GridCornerAtlas`] cell by cell, for tiles generated by code instead of an authored sheet
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tileset::{GridCornerAtlas, RgbaImage};
+/// let mut builder = GridCornerAtlas::builder();
+/// for mask in 0..16 {
+///     builder.mask(mask, RgbaImage::new(32, 32));
+/// }
+/// let atlas = builder.count(0b1111, 3).build().unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct GridCornerAtlasBuilder {
+    key: String,
+    cells: [Vec<RgbaImage>; 16],
+    count: [u8; 16],
+}
+
+impl GridCornerAtlas {
+    /// Create an empty [`GridCornerAtlasBuilder`].
+    pub fn builder() -> GridCornerAtlasBuilder {
+        GridCornerAtlasBuilder::default()
+    }
+}
+
+impl GridCornerAtlasBuilder {
+    /// Set the key of the atlas.
+    pub fn key(&mut self, key: &str) -> &mut Self {
+        self.key = key.to_string();
+        self
+    }
+    /// Add a variant to the corner mask, the first call sets the main cell.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`.
+    pub fn mask(&mut self, mask: u8, cell: RgbaImage) -> &mut Self {
+        assert!(mask < 16, "corner mask {} is not in range [0b0000, 0b1111]", mask);
+        self.cells[mask as usize].push(cell);
+        self
+    }
+    /// Set the number of variants of the corner mask, variants that were not added are transparent.
+    ///
+    /// Without a count, the mask has as many variants as were added, or a single transparent cell if none were.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`, or the count is zero.
+    pub fn count(&mut self, mask: u8, count: u8) -> &mut Self {
+        assert!(mask < 16, "corner mask {} is not in range [0b0000, 0b1111]", mask);
+        assert!(count > 0, "the count of mask 0b{:04b} must be greater than zero", mask);
+        self.count[mask as usize] = count;
+        self
+    }
+    /// Pack the cells into an atlas, all cells must have the same size and no mask may have more cells than its count.
+    pub fn build(&self) -> ImageResult<GridCornerAtlas> {
+        let Some(first) = self.cells.iter().flatten().next()
+        else {
+            return io_error("The atlas needs at least one cell to know the cell size", ErrorKind::InvalidInput);
+        };
+        let (cell_w, cell_h) = first.dimensions();
+        if cell_w == 0 || cell_h == 0 {
+            io_error("The cell width and height must be greater than zero", ErrorKind::InvalidInput)?
+        }
+        let mut count = [1; 16];
+        let mut cache = vec![];
+        for (mask, cells) in self.cells.iter().enumerate() {
+            if let Some(cell) = cells.iter().find(|c| c.dimensions() != (cell_w, cell_h)) {
+                io_error(
+                    format!(
+                        "The cell of mask 0b{:04b} is {:?}, but the cell size is {:?}",
+                        mask,
+                        cell.dimensions(),
+                        (cell_w, cell_h)
+                    ),
+                    ErrorKind::InvalidInput,
+                )?
+            }
+            count[mask] = match self.count[mask] {
+                0 => cells.len().clamp(1, u8::MAX as usize) as u8,
+                n if cells.len() > n as usize => io_error(
+                    format!("The mask 0b{:04b} has {} cells, but the count is {}", mask, cells.len(), n),
+                    ErrorKind::InvalidInput,
+                )?,
+                n => n,
+            };
+            for index in 0..count[mask] as usize {
+                let cell = cells.get(index).cloned().unwrap_or_else(|| RgbaImage::new(cell_w, cell_h));
+                cache.push(Arc::new(cell));
+            }
+        }
+        Ok(GridCornerAtlas { key: self.key.clone(), cell_w, cell_h, count, cache, ..Default::default() })
+    }
+}
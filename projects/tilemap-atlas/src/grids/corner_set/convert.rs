@@ -0,0 +1,303 @@
+use super::*;
+use crate::{
+    grids::corner_wang::{view_wang4x4c_cell, wang4x4c_position},
+    traits::dimension_error,
+};
+use image::{ImageError, Rgba};
+
+impl GridCornerAtlas {
+    /// Create a corner atlas from a 4x4 wang corner sheet.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let raw = image::open("assets/wang4x4c.png").unwrap().to_rgba8();
+    /// let atlas = GridCornerAtlas::from_wang(&raw).unwrap();
+    /// ```
+    pub fn from_wang(image: &RgbaImage) -> ImageResult<Self> {
+        let (w, h) = image.dimensions();
+        if w % 4 != 0 || h % 4 != 0 || w == 0 || h == 0 {
+            io_error("The image width and height must be a non-zero multiple of 4", ErrorKind::InvalidInput)?;
+        }
+        let cache = (0..16).map(|mask| Arc::new(view_wang4x4c_cell(image, mask).to_image())).collect();
+        Ok(Self { cell_w: w / 4, cell_h: h / 4, cache, ..Default::default() })
+    }
+    /// Check a 4x4 wang corner sheet and report every problem at once, instead of stopping at the first one like
+    /// [`GridCornerAtlas::from_wang`].
+    ///
+    /// Besides the size of the sheet, the cells of masks other than `0b0000` must not be fully transparent, since every
+    /// one of them shows some terrain.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let raw = image::open("assets/wang4x4c.png").unwrap().to_rgba8();
+    /// if let Err(problems) = GridCornerAtlas::validate_all(&raw) {
+    ///     for problem in problems {
+    ///         println!("{}", problem);
+    ///     }
+    /// }
+    /// ```
+    pub fn validate_all(image: &RgbaImage) -> Result<(), Vec<ImageError>> {
+        let (w, h) = image.dimensions();
+        let mut problems = vec![];
+        let mut report =
+            |message: String| problems.push(ImageError::IoError(std::io::Error::new(ErrorKind::InvalidInput, message)));
+        if w == 0 || w % 4 != 0 {
+            report(format!("The image width {} must be a non-zero multiple of 4", w));
+        }
+        if h == 0 || h % 4 != 0 {
+            report(format!("The image height {} must be a non-zero multiple of 4", h));
+        }
+        let (cell_w, cell_h) = (w / 4, h / 4);
+        if cell_w != 0 && cell_h != 0 {
+            for mask in 1..16 {
+                let (x, y) = wang4x4c_position(mask);
+                let cell = image.view(x * cell_w, y * cell_h, cell_w, cell_h);
+                if cell.pixels().all(|(_, _, p)| p[3] == 0) {
+                    report(format!("The cell of mask 0b{:04b} at column {} row {} is fully transparent", mask, x, y));
+                }
+            }
+        }
+        match problems.is_empty() {
+            true => Ok(()),
+            false => Err(problems),
+        }
+    }
+    /// Create a corner atlas from a 4x4 wang block inside a larger sheet, such as a master sheet with many terrains.
+    ///
+    /// # Arguments
+    ///
+    /// * `(x, y)`: The pixel offset of the block
+    /// * `(w, h)`: The size of a single cell
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let raw = image::open("assets/terrains.png").unwrap().to_rgba8();
+    /// let atlas = GridCornerAtlas::from_wang_region(&raw, (128, 0), (32, 32)).unwrap();
+    /// ```
+    pub fn from_wang_region(image: &RgbaImage, (x, y): (u32, u32), (w, h): (u32, u32)) -> ImageResult<Self> {
+        let max_x = w.checked_mul(4).and_then(|w| x.checked_add(w));
+        let max_y = h.checked_mul(4).and_then(|h| y.checked_add(h));
+        match (max_x, max_y) {
+            (Some(max_x), Some(max_y)) if max_x <= image.width() && max_y <= image.height() && w != 0 && h != 0 => {}
+            _ => io_error("The image size has out of range", ErrorKind::InvalidInput)?,
+        }
+        let view = image::imageops::crop_imm(image, x, y, w * 4, h * 4);
+        Self::from_wang(&view.to_image())
+    }
+    /// Create a corner atlas from a 4x4 wang corner sheet with a gutter between the cells, but not around the sheet.
+    ///
+    /// # Arguments
+    ///
+    /// * `gutter`: The width of the gutter in pixels, `0` is the same as [`GridCornerAtlas::from_wang`]
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let raw = image::open("assets/wang4x4c-gutter.png").unwrap().to_rgba8();
+    /// let atlas = GridCornerAtlas::from_wang_gutter(&raw, 1).unwrap();
+    /// ```
+    pub fn from_wang_gutter(image: &RgbaImage, gutter: u32) -> ImageResult<Self> {
+        let (w, h) = image.dimensions();
+        let cell = |size: u32| match size.checked_sub(gutter.saturating_mul(3)) {
+            Some(s) if s != 0 && s % 4 == 0 => Some(s / 4),
+            _ => None,
+        };
+        let (cell_w, cell_h) = match (cell(w), cell(h)) {
+            (Some(cell_w), Some(cell_h)) => (cell_w, cell_h),
+            _ => io_error(
+                format!("The image size minus 3 gutters of {}px must be a non-zero multiple of 4", gutter),
+                ErrorKind::InvalidInput,
+            )?,
+        };
+        let cache = (0..16)
+            .map(|mask| {
+                let (col, row) = wang4x4c_position(mask);
+                Arc::new(image.view(col * (cell_w + gutter), row * (cell_h + gutter), cell_w, cell_h).to_image())
+            })
+            .collect();
+        Ok(Self { cell_w, cell_h, cache, ..Default::default() })
+    }
+}
+
+impl GridCornerAtlas {
+    /// Suggest a crop rect `(x, y, w, h)` that makes a 4x4 wang sheet cleanly divisible, the image is not modified.
+    ///
+    /// Sheets exported one pixel too wide or too high shift every cell, the excess pixels are taken from the transparent
+    /// edges first, otherwise from the right and bottom.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let raw = image::open("assets/wang4x4c.png").unwrap().to_rgba8();
+    /// let (x, y, w, h) = GridCornerAtlas::try_align(&raw).unwrap();
+    /// let atlas = GridCornerAtlas::from_wang(&image::imageops::crop_imm(&raw, x, y, w, h).to_image())
+    ///     .unwrap();
+    /// ```
+    pub fn try_align(image: &RgbaImage) -> ImageResult<(u32, u32, u32, u32)> {
+        let (w, h) = image.dimensions();
+        let (excess_x, excess_y) = (w % 4, h % 4);
+        if w < 4 || h < 4 {
+            dimension_error()?;
+        }
+        let transparent_column = |x: u32| (0..h).all(|y| image.get_pixel(x, y)[3] == 0);
+        let transparent_row = |y: u32| (0..w).all(|x| image.get_pixel(x, y)[3] == 0);
+        let x = (0..excess_x).take_while(|x| transparent_column(*x)).count() as u32;
+        let y = (0..excess_y).take_while(|y| transparent_row(*y)).count() as u32;
+        Ok((x, y, w - excess_x, h - excess_y))
+    }
+}
+
+impl GridCornerAtlas {
+    /// Create a corner atlas from the standard form, a row of 16 square cells with variants placed directly below.
+    ///
+    /// The variants of each mask end at the first fully transparent cell in its column.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// ```
+    pub fn from_standard(image: &RgbaImage) -> ImageResult<Self> {
+        let (w, h) = image.dimensions();
+        let cell = w / 16;
+        if w % 16 != 0 || w == 0 || h == 0 || h % cell != 0 {
+            io_error(
+                "The image width must be a non-zero multiple of 16 and the image height must be a non-zero multiple of the cell width",
+                ErrorKind::InvalidInput,
+            )?;
+        }
+        let rows = (h / cell).min(u8::MAX as u32);
+        let mut count = [1; 16];
+        let mut cache = Vec::with_capacity(16);
+        for mask in 0..16 {
+            cache.push(Arc::new(image.view(mask * cell, 0, cell, cell).to_image()));
+            for row in 1..rows {
+                let view = image.view(mask * cell, row * cell, cell, cell);
+                if view.pixels().all(|(_, _, p)| p[3] == 0) {
+                    break;
+                }
+                cache.push(Arc::new(view.to_image()));
+                count[mask as usize] += 1;
+            }
+        }
+        Ok(Self { cell_w: cell, cell_h: cell, count, cache, ..Default::default() })
+    }
+    /// Create a corner atlas from a standard form image file.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// atlas.save("assets/grass-std.png").unwrap();
+    /// ```
+    pub fn load<P>(path: P) -> ImageResult<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Self::from_standard(&open_image(path)?)
+    }
+}
+
+impl GridCornerAtlas {
+    /// Create a corner atlas from a 2x2 mini autotile, every corner quadrant of a mask is picked from one of the 4 cells.
+    ///
+    /// The cells are laid out as
+    ///
+    /// ```text
+    /// | outer corners    | inner fill     |
+    /// | horizontal edges | vertical edges |
+    /// ```
+    ///
+    /// A quadrant whose corner is not covered stays transparent, a covered quadrant takes the inner fill when both
+    /// neighboring corners are covered, the outer corner when neither is, otherwise the matching edge.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let raw = image::open("assets/mini.png").unwrap().to_rgba8();
+    /// let atlas = GridCornerAtlas::from_mini(&raw, raw.width() / 2, raw.height() / 2).unwrap();
+    /// ```
+    pub fn from_mini(image: &RgbaImage, cell_w: u32, cell_h: u32) -> ImageResult<Self> {
+        let even = cell_w % 2 == 0 && cell_h % 2 == 0 && cell_w != 0 && cell_h != 0;
+        if image.dimensions() != (cell_w * 2, cell_h * 2) || !even {
+            io_error(
+                "The image must be 2x2 cells, and the cell size must be a non-zero multiple of 2",
+                ErrorKind::InvalidInput,
+            )?;
+        }
+        let (half_w, half_h) = (cell_w / 2, cell_h / 2);
+        let mut cache = Vec::with_capacity(16);
+        for mask in 0..16u8 {
+            let mut cell = RgbaImage::new(cell_w, cell_h);
+            // quadrants in the order of the mask bits: lu, ru, ld, rd
+            for i in 0..4u8 {
+                let covered = |bit: u8| mask >> bit & 1 == 1;
+                let (sx, sy) = match (covered(i), covered(i ^ 1), covered(i ^ 2)) {
+                    (false, _, _) => continue,
+                    (true, false, false) => (0, 0),
+                    (true, true, true) => (1, 0),
+                    (true, true, false) => (0, 1),
+                    (true, false, true) => (1, 1),
+                };
+                let (qx, qy) = ((i as u32 % 2) * half_w, (i as u32 / 2) * half_h);
+                let view = image.view(sx * cell_w + qx, sy * cell_h + qy, half_w, half_h);
+                cell.copy_from(&*view, qx, qy)?;
+            }
+            cache.push(Arc::new(cell));
+        }
+        Ok(Self { cell_w, cell_h, cache, ..Default::default() })
+    }
+    /// Create a solid color atlas for prototyping maps before the art exists.
+    ///
+    /// Covered quadrants take the color and uncovered quadrants a darker shade of it, so every mask looks different and
+    /// misplaced tiles are easy to spot.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::placeholder(32, 32, image::Rgba([255, 0, 255, 255])).unwrap();
+    /// ```
+    pub fn placeholder(cell_w: u32, cell_h: u32, color: Rgba<u8>) -> ImageResult<Self> {
+        if cell_w < 2 || cell_h < 2 {
+            io_error("The cell width and height must be at least 2 pixels", ErrorKind::InvalidInput)?;
+        }
+        let Rgba([r, g, b, a]) = color;
+        let shade = Rgba([r / 3, g / 3, b / 3, a]);
+        let cache = (0..16u8)
+            .map(|mask| {
+                Arc::new(RgbaImage::from_fn(cell_w, cell_h, |x, y| {
+                    // the quadrant bits follow the mask bits: lu, ru, ld, rd
+                    let bit = (x >= cell_w / 2) as u8 | ((y >= cell_h / 2) as u8) << 1;
+                    if mask >> bit & 1 == 1 {
+                        color
+                    }
+                    else {
+                        shade
+                    }
+                }))
+            })
+            .collect();
+        Ok(Self { cell_w, cell_h, cache, ..Default::default() })
+    }
+}
+
+/// Create a corner atlas from a 4x4 wang corner sheet, see [`GridCornerAtlas::from_wang`].
+impl TryFrom<RgbaImage> for GridCornerAtlas {
+    type Error = image::ImageError;
+
+    fn try_from(image: RgbaImage) -> ImageResult<Self> {
+        Self::from_wang(&image)
+    }
+}
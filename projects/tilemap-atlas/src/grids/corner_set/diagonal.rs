@@ -0,0 +1,39 @@
+use super::*;
+
+impl GridCornerAtlas {
+    /// Load the cells of diagonal-only connections, such as bridges and stairs that only touch a diagonal neighbor.
+    ///
+    /// These cells are not part of the standard form, they live in their own strip of 16 cells laid out like the first
+    /// row of the standard form: cell `i` is drawn for the diagonal mask `i`, whose bits are nw, ne, sw, se in the same
+    /// order as the corner mask. Cell `0` means no diagonal connection and is never read, fully transparent cells are
+    /// left out.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let mut atlas = GridCornerAtlas::load("assets/bridge-std.png").unwrap();
+    /// let strip = image::open("assets/bridge-diagonal.png").unwrap().to_rgba8();
+    /// atlas.set_diagonal_strip(&strip).unwrap();
+    /// ```
+    pub fn set_diagonal_strip(&mut self, strip: &RgbaImage) -> ImageResult<()> {
+        if strip.width() != self.cell_w * 16 || strip.height() != self.cell_h {
+            dimension_error()?
+        }
+        self.diagonal.clear();
+        for mask in 1..16u8 {
+            let cell = strip.view(mask as u32 * self.cell_w, 0, self.cell_w, self.cell_h).to_image();
+            if cell.pixels().any(|p| p[3] != 0) {
+                self.diagonal.insert(mask, Arc::new(cell));
+            }
+        }
+        Ok(())
+    }
+    /// Get the authored cell of a tile that connects only to its diagonal neighbors, `None` if there is no such cell.
+    ///
+    /// Use the standard corner masks when this returns `None`, see [`GridCornerAtlas::set_diagonal_strip`] for the
+    /// layout of these cells.
+    pub fn get_diagonal(&self, ne: bool, nw: bool, se: bool, sw: bool) -> Option<&RgbaImage> {
+        self.diagonal.get(&grid_corner_mask(nw, ne, sw, se)).map(|cell| cell.as_ref())
+    }
+}
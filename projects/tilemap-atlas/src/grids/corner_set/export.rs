@@ -0,0 +1,94 @@
+use super::*;
+use crate::grids::corner_wang::wang4x4c_position;
+
+impl GridCornerAtlas {
+    /// Render the atlas in standard form, a row of 16 cells with variants placed directly below.
+    pub fn to_image(&self) -> RgbaImage {
+        let rows = self.count.iter().copied().max().unwrap_or(1) as u32;
+        let mut output = RgbaImage::new(self.cell_w * 16, self.cell_h * rows);
+        for mask in 0..16u8 {
+            for index in 0..self.count[mask as usize] {
+                let cell = self.get_corner_variant(mask, index);
+                image::imageops::replace(
+                    &mut output,
+                    cell,
+                    (mask as u32 * self.cell_w) as i64,
+                    (index as u32 * self.cell_h) as i64,
+                );
+            }
+        }
+        output
+    }
+    /// Render the atlas in standard form, upside down if `flip_y` is set, for engines whose textures are y-up.
+    ///
+    /// Pair it with [`GridCornerAtlas::to_manifest_with`] using the same flag to get matching rects.
+    pub fn to_image_with(&self, flip_y: bool) -> RgbaImage {
+        let output = self.to_image();
+        match flip_y {
+            true => image::imageops::flip_vertical(&output),
+            false => output,
+        }
+    }
+    /// Render the first cell of each mask as a 4x4 wang corner sheet.
+    pub fn to_wang(&self) -> RgbaImage {
+        let mut output = RgbaImage::new(self.cell_w * 4, self.cell_h * 4);
+        for mask in 0..16 {
+            let (x, y) = wang4x4c_position(mask);
+            image::imageops::replace(
+                &mut output,
+                self.get_corner_mask(mask),
+                (x * self.cell_w) as i64,
+                (y * self.cell_h) as i64,
+            );
+        }
+        output
+    }
+    /// Save the atlas in standard form to a png file, remember you need add `.png` suffix.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// atlas.save("assets/grass-std.png").unwrap();
+    /// ```
+    pub fn save<P>(&self, path: P) -> ImageResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        save_as_png(&self.to_image(), path)
+    }
+    /// Render the first cell of the given masks as a horizontal strip, in the order the engine expects.
+    ///
+    /// The order may be a subset of the 16 masks, but every mask must be in range `0b0000..=0b1111` and appear only once.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// let strip = atlas.to_strip(&[0b1111, 0b0000, 0b0001, 0b0010]).unwrap();
+    /// ```
+    pub fn to_strip(&self, order: &[u8]) -> ImageResult<RgbaImage> {
+        let mut seen = [false; 16];
+        for mask in order {
+            match seen.get_mut(*mask as usize) {
+                Some(s) if !*s => *s = true,
+                Some(_) => io_error(format!("The mask 0b{:04b} appears more than once", mask), ErrorKind::InvalidInput)?,
+                None => io_error(format!("The mask {} is not in range [0b0000, 0b1111]", mask), ErrorKind::InvalidInput)?,
+            }
+        }
+        let mut output = RgbaImage::new(self.cell_w * order.len() as u32, self.cell_h);
+        for (i, mask) in order.iter().enumerate() {
+            image::imageops::replace(&mut output, self.get_corner_mask(*mask), (i as u32 * self.cell_w) as i64, 0);
+        }
+        Ok(output)
+    }
+    /// Split the atlas into 16 separate cells for texture array uploads.
+    ///
+    /// Layer `i` holds the first cell of mask `i`, so the layer index can be used as the corner mask directly, variants
+    /// are left out.
+    pub fn to_layers(&self) -> Vec<RgbaImage> {
+        (0..16).map(|mask| self.get_corner_mask(mask).clone()).collect()
+    }
+}
@@ -0,0 +1,80 @@
+use super::*;
+use image::Rgba;
+use png::{BitDepth, ColorType, Encoder};
+use std::{collections::HashMap, fs::File, io::BufWriter};
+
+impl GridCornerAtlas {
+    /// Save the atlas in standard form as an 8-bit indexed png with the given palette, so recolorable atlases stay
+    /// indexed, remember you need add `.png` suffix.
+    ///
+    /// Every color must be in the palette, otherwise an error is returned, unless `snap` is set and the color is
+    /// replaced by the nearest palette entry. Translucent palette entries are kept in the `tRNS` chunk.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use image::Rgba;
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// let palette = [Rgba([0, 0, 0, 0]), Rgba([67, 141, 23, 255]), Rgba([92, 156, 37, 255])];
+    /// atlas.export_indexed("assets/grass-indexed.png", &palette, true).unwrap();
+    /// ```
+    pub fn export_indexed<P>(&self, path: P, palette: &[Rgba<u8>], snap: bool) -> ImageResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        if palette.is_empty() || palette.len() > 256 {
+            io_error(format!("The palette must have 1 to 256 colors, found {}", palette.len()), ErrorKind::InvalidInput)?
+        }
+        let image = self.to_image();
+        let mut lookup: HashMap<[u8; 4], u8> = HashMap::new();
+        // inserted in reverse so the first entry wins if a color is listed twice
+        for (i, color) in palette.iter().enumerate().rev() {
+            lookup.insert(color.0, i as u8);
+        }
+        let mut indices = Vec::with_capacity((image.width() * image.height()) as usize);
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let index = match lookup.get(&pixel.0) {
+                Some(i) => *i,
+                None if snap => {
+                    let i = nearest(palette, pixel);
+                    lookup.insert(pixel.0, i);
+                    i
+                }
+                None => io_error(
+                    format!(
+                        "The color {:?} of mask 0b{:04b} at ({}, {}) is not in the palette",
+                        pixel.0,
+                        x / self.cell_w.max(1),
+                        x,
+                        y
+                    ),
+                    ErrorKind::InvalidData,
+                )?,
+            };
+            indices.push(index);
+        }
+        let path = path.as_ref();
+        if let Some(s) = path.parent() {
+            std::fs::create_dir_all(s)?
+        }
+        let mut encoder = Encoder::new(BufWriter::new(File::create(path)?), image.width(), image.height());
+        encoder.set_color(ColorType::Indexed);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_palette(palette.iter().flat_map(|c| [c[0], c[1], c[2]]).collect::<Vec<_>>());
+        if palette.iter().any(|c| c[3] != 255) {
+            encoder.set_trns(palette.iter().map(|c| c[3]).collect::<Vec<_>>());
+        }
+        let result = encoder.write_header().and_then(|mut writer| writer.write_image_data(&indices));
+        match result {
+            Ok(()) => Ok(()),
+            Err(e) => io_error(format!("The file {} can not be written: {}", path.display(), e), ErrorKind::Other),
+        }
+    }
+}
+
+/// The palette entry with the smallest squared distance over all four channels.
+fn nearest(palette: &[Rgba<u8>], color: &Rgba<u8>) -> u8 {
+    let distance = |c: &Rgba<u8>| (0..4).map(|i| (c[i] as i32 - color[i] as i32).pow(2)).sum::<i32>();
+    palette.iter().enumerate().min_by_key(|(_, c)| distance(c)).map(|(i, _)| i as u8).unwrap_or(0)
+}
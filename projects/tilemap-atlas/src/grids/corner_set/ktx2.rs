@@ -0,0 +1,101 @@
+use super::*;
+use intel_tex_2::{bc7, RgbaSurface};
+
+/// The block compression used by [`GridCornerAtlas::export_ktx2`]
+///
+/// ASTC is not offered yet, the ASTC entry of intel_tex_2 stops at `unimplemented!()` before writing any block. The
+/// enum is non exhaustive so it can be added once an encoder is available.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum CompressionFormat {
+    /// BC7 with alpha, 4x4 blocks of 16 bytes, for desktop and console GPUs
+    Bc7,
+}
+
+impl CompressionFormat {
+    /// The `VkFormat` of the sRGB variant
+    fn vk_format(self) -> u32 {
+        match self {
+            CompressionFormat::Bc7 => 146,
+        }
+    }
+    /// The color model of the data format descriptor
+    fn color_model(self) -> u8 {
+        match self {
+            CompressionFormat::Bc7 => 134,
+        }
+    }
+}
+
+impl GridCornerAtlas {
+    /// Block compress the atlas in standard form and save it as a KTX2 texture, remember you need add `.ktx2` suffix.
+    ///
+    /// The image is padded with transparent pixels to whole blocks, the texture keeps the size of the atlas.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::{CompressionFormat, GridCornerAtlas};
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// atlas.export_ktx2("assets/grass-std.ktx2", CompressionFormat::Bc7).unwrap();
+    /// ```
+    pub fn export_ktx2<P>(&self, path: P, format: CompressionFormat) -> ImageResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        if let Some(s) = path.parent() {
+            std::fs::create_dir_all(s)?
+        }
+        Ok(std::fs::write(path, self.to_ktx2(format))?)
+    }
+    /// Block compress the atlas in standard form into the bytes of a KTX2 texture.
+    pub fn to_ktx2(&self, format: CompressionFormat) -> Vec<u8> {
+        let image = self.to_image();
+        let (w, h) = image.dimensions();
+        let mut padded = RgbaImage::new(w.div_ceil(4) * 4, h.div_ceil(4) * 4);
+        image::imageops::replace(&mut padded, &image, 0, 0);
+        let surface =
+            RgbaSurface { data: padded.as_raw(), width: padded.width(), height: padded.height(), stride: padded.width() * 4 };
+        let blocks = match format {
+            CompressionFormat::Bc7 => bc7::compress_blocks(&bc7::alpha_basic_settings(), &surface),
+        };
+        write_ktx2(format, w, h, &blocks)
+    }
+}
+
+/// Write a single level, single layer 2D texture, see <https://registry.khronos.org/KTX/specs/2.0/ktxspec.v2.html>.
+fn write_ktx2(format: CompressionFormat, width: u32, height: u32, blocks: &[u8]) -> Vec<u8> {
+    const HEADER: usize = 80;
+    const LEVELS: usize = 24;
+    const DFD: usize = 44;
+    // level data is aligned to the 16 bytes block size
+    let level_offset = (HEADER + LEVELS + DFD).div_ceil(16) * 16;
+    let mut out = Vec::with_capacity(level_offset + blocks.len());
+    out.extend_from_slice(&[0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n']);
+    let words = [format.vk_format(), 1, width, height, 0, 0, 1, 1, 0, (HEADER + LEVELS) as u32, DFD as u32, 0, 0];
+    words.iter().for_each(|w| out.extend_from_slice(&w.to_le_bytes()));
+    // no supercompression global data
+    out.extend_from_slice(&[0; 16]);
+    for value in [level_offset, blocks.len(), blocks.len()] {
+        out.extend_from_slice(&(value as u64).to_le_bytes());
+    }
+    // basic data format descriptor with one 128 bits sample, BT.709 primaries and sRGB transfer
+    let dfd = [
+        DFD as u32,
+        0,
+        2 | 40 << 16,
+        u32::from_le_bytes([format.color_model(), 1, 2, 0]),
+        u32::from_le_bytes([3, 3, 0, 0]),
+        16,
+        0,
+        127 << 16,
+        0,
+        0,
+        u32::MAX,
+    ];
+    dfd.iter().for_each(|w| out.extend_from_slice(&w.to_le_bytes()));
+    out.resize(level_offset, 0);
+    out.extend_from_slice(blocks);
+    out
+}
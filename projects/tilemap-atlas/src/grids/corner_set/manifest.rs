@@ -0,0 +1,97 @@
+use super::*;
+
+/// The layout of an atlas exported by [`GridCornerAtlas::to_image`], so engines can locate every cell without the crate
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CornerManifest {
+    /// The key of the exported atlas
+    pub key: String,
+    /// The width of the cell in pixels
+    pub cell_w: u32,
+    /// The height of the cell in pixels
+    pub cell_h: u32,
+    /// Whether the rects are measured from the bottom left corner for y-up engines, instead of the top left corner
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flip_y: bool,
+    /// The frames of each mask, indexed by the corner mask
+    pub masks: Vec<CornerManifestMask>,
+}
+
+impl CornerManifest {
+    /// Switch the rects between the y-down and the y-up origin, flipping twice restores the original layout.
+    pub fn flip_y(&mut self) {
+        let height = self.masks.iter().map(|m| m.frames.len() as u32).max().unwrap_or(1) * self.cell_h;
+        for frame in self.masks.iter_mut().flat_map(|m| m.frames.iter_mut()) {
+            let (x, y, w, h) = frame.rect;
+            frame.rect = (x, height - y - h, w, h);
+        }
+        self.flip_y = !self.flip_y;
+    }
+}
+
+/// The frames of one corner mask in a [`CornerManifest`]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CornerManifestMask {
+    /// The corner mask, in range `0b0000..=0b1111`
+    pub mask: u8,
+    /// The variants of the mask, played in order when the atlas is animated
+    pub frames: Vec<CornerFrame>,
+}
+
+/// A single cell in a [`CornerManifest`]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CornerFrame {
+    /// The pixel rect of the cell in the exported image, `(x, y, w, h)`
+    pub rect: (u32, u32, u32, u32),
+    /// How long the frame is shown in milliseconds, `0` means the variants are not animated
+    pub duration_ms: u32,
+}
+
+impl GridCornerAtlas {
+    /// Describe the layout of [`GridCornerAtlas::to_image`], including the frame rects and durations of each mask.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/water-std.png").unwrap();
+    /// atlas.save("assets/water-std.png").unwrap();
+    /// let manifest = serde_json::to_string_pretty(&atlas.to_manifest()).unwrap();
+    /// std::fs::write("assets/water-std.json", manifest).unwrap();
+    /// ```
+    pub fn to_manifest(&self) -> CornerManifest {
+        self.to_manifest_with(false)
+    }
+    /// Describe the layout of [`GridCornerAtlas::to_image_with`], the rects are measured from the bottom left corner if
+    /// `flip_y` is set.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/water-std.png").unwrap();
+    /// let image = atlas.to_image_with(true);
+    /// let manifest = atlas.to_manifest_with(true);
+    /// ```
+    pub fn to_manifest_with(&self, flip_y: bool) -> CornerManifest {
+        let masks = (0..16u8)
+            .map(|mask| {
+                let frames = (0..self.count[mask as usize] as u32)
+                    .map(|index| CornerFrame {
+                        rect: (mask as u32 * self.cell_w, index * self.cell_h, self.cell_w, self.cell_h),
+                        duration_ms: self.frame_ms,
+                    })
+                    .collect();
+                CornerManifestMask { mask, frames }
+            })
+            .collect();
+        let mut manifest =
+            CornerManifest { key: self.key.clone(), cell_w: self.cell_w, cell_h: self.cell_h, flip_y: false, masks };
+        if flip_y {
+            manifest.flip_y();
+        }
+        manifest
+    }
+}
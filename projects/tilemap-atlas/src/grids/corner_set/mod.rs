@@ -1,5 +1,35 @@
 use super::*;
-use crate::traits::dimension_error;
+
+mod builder;
+mod convert;
+mod diagonal;
+mod export;
+mod indexed;
+#[cfg(feature = "ktx2")]
+mod ktx2;
+mod manifest;
+#[cfg(feature = "ora")]
+mod ora;
+mod scale;
+mod sdf;
+#[cfg(feature = "tiled")]
+mod tiled_wang;
+mod trim;
+
+#[cfg(feature = "ktx2")]
+pub use self::ktx2::CompressionFormat;
+pub(crate) use self::scale::integer_scale;
+pub use self::{
+    builder::GridCornerAtlasBuilder,
+    manifest::{CornerFrame, CornerManifest, CornerManifestMask},
+    trim::{TrimmedAtlas, TrimmedFrame},
+};
+use crate::{traits::dimension_error, utils::grid_corner_mask};
+use image::Rgba;
+use rand_core::RngCore;
+#[cfg(feature = "serde")]
+use serde::{de::Error, Deserializer};
+use std::cmp::Ordering;
 
 /// A tile atlas for gridded maps
 ///
@@ -18,13 +48,271 @@ use crate::traits::dimension_error;
 /// ```no_run
 /// # use tileset::GridCornerAtlas;
 /// ```
-#[derive(Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GridCornerAtlas {
     pub(crate) key: String,
     pub(crate) cell_w: u32,
     pub(crate) cell_h: u32,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_count"))]
     pub(crate) count: [u8; 16],
+    /// The duration of each animation frame in milliseconds, `0` means the variants are not animated
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) frame_ms: u32,
+    /// Gameplay metadata of each mask, such as `slippery` or `damaging`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "BTreeMap::is_empty"))]
+    pub(crate) tags: BTreeMap<u8, Vec<String>>,
+    /// The credit line required by the creators of the atlas
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) attribution: Option<String>,
+    /// The license of the atlas, such as an SPDX identifier
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub(crate) license: Option<String>,
+    /// The solid quadrants of each mask, the bits follow the corner mask: lu, ru, ld, rd
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub(crate) collision: [u8; 16],
+    /// The decoded cells, grouped by mask, `count[mask]` cells for each mask, identical cells may be shared
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) cache: Vec<Arc<RgbaImage>>,
+    /// The opacity applied to the cached cells of each mask, `255` means unchanged
+    #[cfg_attr(feature = "serde", serde(skip, default = "full_opacity"))]
+    pub(crate) opacity: [u8; 16],
+    /// The authored cells of the masks whose opacity is not `255`, the cached cells are faded from them
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) authored: BTreeMap<u8, Vec<Arc<RgbaImage>>>,
+    /// The authored cells of diagonal-only connections, keyed by the diagonal mask, see [`GridCornerAtlas::get_diagonal`]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) diagonal: BTreeMap<u8, Arc<RgbaImage>>,
+    /// The placeholder returned for masks without data
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) fallback: Option<Arc<RgbaImage>>,
+}
+
+impl Default for GridCornerAtlas {
+    fn default() -> Self {
+        Self {
+            key: String::new(),
+            cell_w: 0,
+            cell_h: 0,
+            count: [1; 16],
+            frame_ms: 0,
+            tags: BTreeMap::new(),
+            attribution: None,
+            license: None,
+            collision: [0; 16],
+            cache: vec![],
+            opacity: full_opacity(),
+            authored: BTreeMap::new(),
+            diagonal: BTreeMap::new(),
+            fallback: None,
+        }
+    }
+}
+
+/// Atlases are ordered by key, cell size and counts, the metadata and the decoded cells only break ties
+impl Ord for GridCornerAtlas {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.key, self.cell_w, self.cell_h, self.count)
+            .cmp(&(&other.key, other.cell_w, other.cell_h, other.count))
+            .then_with(|| {
+                (self.frame_ms, &self.tags, &self.attribution, &self.license, self.collision, self.opacity).cmp(&(
+                    other.frame_ms,
+                    &other.tags,
+                    &other.attribution,
+                    &other.license,
+                    other.collision,
+                    other.opacity,
+                ))
+            })
+            .then_with(|| self.cache.iter().map(|c| pixels(c)).cmp(other.cache.iter().map(|c| pixels(c))))
+            .then_with(|| {
+                let lhs = self.authored.iter().map(|(mask, cells)| (mask, cells.iter().map(|c| pixels(c)).collect::<Vec<_>>()));
+                let rhs =
+                    other.authored.iter().map(|(mask, cells)| (mask, cells.iter().map(|c| pixels(c)).collect::<Vec<_>>()));
+                lhs.cmp(rhs)
+            })
+            .then_with(|| {
+                self.diagonal
+                    .iter()
+                    .map(|(mask, c)| (mask, pixels(c)))
+                    .cmp(other.diagonal.iter().map(|(mask, c)| (mask, pixels(c))))
+            })
+            .then_with(|| self.fallback.as_deref().map(pixels).cmp(&other.fallback.as_deref().map(pixels)))
+    }
+}
+
+impl PartialOrd for GridCornerAtlas {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The fields of an image that take part in equality
+pub(crate) fn pixels(image: &RgbaImage) -> (u32, u32, &[u8]) {
+    (image.width(), image.height(), image.as_raw())
+}
+
+fn full_opacity() -> [u8; 16] {
+    [255; 16]
+}
+
+fn fade_cell(cell: &RgbaImage, opacity: u8) -> RgbaImage {
+    let mut out = cell.clone();
+    for pixel in out.pixels_mut() {
+        pixel[3] = ((pixel[3] as u32 * opacity as u32 + 127) / 255) as u8;
+    }
+    out
+}
+
+/// Every mask must have at least one cell, otherwise lookups into the atlas will fail later.
+#[cfg(feature = "serde")]
+fn deserialize_count<'de, D>(deserializer: D) -> Result<[u8; 16], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let count = <[u8; 16]>::deserialize(deserializer)?;
+    if let Some(mask) = count.iter().position(|c| *c == 0) {
+        return Err(D::Error::custom(format!("the count of mask 0b{:04b} must be greater than zero", mask)));
+    }
+    Ok(count)
+}
+
+/// Setters
+impl GridCornerAtlas {
+    /// Point the atlas at another image key, the decoded cells are kept.
+    pub fn with_key(mut self, key: &str) -> Self {
+        self.set_key(key);
+        self
+    }
+    /// Point the atlas at another image key, the decoded cells are kept.
+    pub fn set_key(&mut self, key: &str) {
+        self.key = key.to_string();
+    }
+    /// Attach a gameplay tag to the corner mask, adding the same tag twice has no effect.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`.
+    pub fn add_tag<S>(&mut self, mask: u8, tag: S)
+    where
+        S: Into<String>,
+    {
+        assert!(mask < 16, "corner mask {} is not in range [0b0000, 0b1111]", mask);
+        let tag = tag.into();
+        let tags = self.tags.entry(mask).or_default();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    /// Mark the solid quadrants of the corner mask, the bits of `solid` follow the corner mask: lu, ru, ld, rd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask or the solid bits are not in range `0b0000..=0b1111`.
+    pub fn set_collision(&mut self, mask: u8, solid: u8) {
+        assert!(mask < 16, "corner mask {} is not in range [0b0000, 0b1111]", mask);
+        assert!(solid < 16, "collision bits {} are not in range [0b0000, 0b1111]", solid);
+        self.collision[mask as usize] = solid;
+    }
+    /// Derive the collision of every mask from the first cell, a quadrant is solid if most of its pixels have an alpha
+    /// of at least `128`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let mut atlas = GridCornerAtlas::load("assets/wall-std.png").unwrap();
+    /// atlas.collision_from_alpha();
+    /// assert_eq!(atlas.collision_for(0b1111), 0b1111);
+    /// ```
+    pub fn collision_from_alpha(&mut self) {
+        for mask in 0..16u8 {
+            let Some(cell) = self.cache.get(self.cell_index(mask, 0))
+            else {
+                continue;
+            };
+            let (w, h) = (cell.width(), cell.height());
+            let mut opaque = [0u32; 4];
+            let mut total = [0u32; 4];
+            for (x, y, pixel) in cell.enumerate_pixels() {
+                let bit = (x >= w / 2) as usize | ((y >= h / 2) as usize) << 1;
+                total[bit] += 1;
+                opaque[bit] += (pixel[3] >= 128) as u32;
+            }
+            self.collision[mask as usize] =
+                (0..4).filter(|i| total[*i] != 0 && opaque[*i] * 2 > total[*i]).fold(0, |solid, i| solid | 1 << i);
+        }
+    }
+    /// Set the credit line required by the creators of the atlas.
+    pub fn set_attribution(&mut self, attribution: Option<&str>) {
+        self.attribution = attribution.map(|s| s.to_string());
+    }
+    /// Set the license of the atlas, such as an SPDX identifier like `CC-BY-4.0`.
+    pub fn set_license(&mut self, license: Option<&str>) {
+        self.license = license.map(|s| s.to_string());
+    }
+    /// Set the placeholder returned by [`GridCornerAtlas::get_corner_or_fallback`] for masks without data, such as a
+    /// magenta cell that is easy to spot on the map.
+    pub fn set_fallback(&mut self, cell: RgbaImage) {
+        self.fallback = Some(Arc::new(cell));
+    }
+    /// Treat the variants of each mask as animation frames that last for the given duration.
+    pub fn set_frame_duration(&mut self, duration: Duration) {
+        self.frame_ms = duration.as_millis().min(u32::MAX as u128) as u32;
+    }
+    /// Scale the alpha of all cells of the corner mask, useful for fading out decorative variants.
+    ///
+    /// The alpha is clamped to `[0, 1]` and replaces the previous opacity. The authored cells are kept aside while the
+    /// mask is faded, so setting the opacity back to `1` restores them exactly, even after fading out to `0`. Fails if the
+    /// cells of the mask are not loaded, for example the atlas was deserialized without its cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask`: The corner mask, in range `0b0000..=0b1111`
+    pub fn set_mask_opacity(&mut self, mask: u8, alpha: f32) -> ImageResult<()> {
+        if mask >= 16 {
+            dimension_error()?
+        }
+        let opacity = (alpha.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let start = self.cell_index(mask, 0);
+        let end = start + self.count[mask as usize] as usize;
+        if end > self.cache.len() {
+            io_error(format!("The cells of mask 0b{:04b} are not loaded", mask), ErrorKind::NotFound)?
+        }
+        let authored = match self.authored.remove(&mask) {
+            Some(cells) => cells,
+            None => self.cache[start..end].to_vec(),
+        };
+        for (cell, source) in self.cache[start..end].iter_mut().zip(&authored) {
+            *cell = match opacity {
+                255 => source.clone(),
+                _ => Arc::new(fade_cell(source, opacity)),
+            };
+        }
+        if opacity != 255 {
+            self.authored.insert(mask, authored);
+        }
+        self.opacity[mask as usize] = opacity;
+        Ok(())
+    }
+}
+
+impl GridCornerAtlas {
+    /// Share the memory of identical cells, returns the number of cells that were merged.
+    ///
+    /// Modifying a shared cell later, for example with [`GridCornerAtlas::set_mask_opacity`], copies it first.
+    pub fn dedup(&mut self) -> usize {
+        let mut merged = 0;
+        for i in 1..self.cache.len() {
+            if let Some(j) = (0..i).find(|j| self.cache[*j] == self.cache[i]) {
+                if !Arc::ptr_eq(&self.cache[i], &self.cache[j]) {
+                    self.cache[i] = self.cache[j].clone();
+                    merged += 1;
+                }
+            }
+        }
+        merged
+    }
 }
 
 /// Getters
@@ -32,15 +320,204 @@ impl GridCornerAtlas {
     pub fn get_key(&self) -> &str {
         &self.key
     }
+    /// Get the width and height of a single cell.
+    pub fn get_cell_size(&self) -> (u32, u32) {
+        (self.cell_w, self.cell_h)
+    }
+    /// Get the credit line required by the creators of the atlas.
+    pub fn get_attribution(&self) -> Option<&str> {
+        self.attribution.as_deref()
+    }
+    /// Get the license of the atlas.
+    pub fn get_license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+    /// Get the first cell of the corner pattern.
+    pub fn get_corner(&self, lu: bool, ru: bool, ld: bool, rd: bool) -> &RgbaImage {
+        self.get_corner_mask(grid_corner_mask(lu, ru, ld, rd))
+    }
+    /// Get the first cell of the corner mask.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`, or the cells are not loaded.
+    pub fn get_corner_mask(&self, mask: u8) -> &RgbaImage {
+        &self.cache[self.cell_index(mask, 0)]
+    }
+    /// Get the first cell of the corner mask, or the given fallback if the mask is out of range or the cells are not
+    /// loaded.
+    pub fn get_corner_or<'a>(&'a self, mask: u8, fallback: &'a RgbaImage) -> &'a RgbaImage {
+        if mask >= 16 {
+            return fallback;
+        }
+        match self.cache.get(self.cell_index(mask, 0)) {
+            Some(cell) => cell,
+            None => fallback,
+        }
+    }
+    /// Get the first cell of the corner mask, following the fallback policy of [`GridCornerAtlas::select_variant`] if the
+    /// mask has no data, returns `None` if there is no fallback either.
+    pub fn get_corner_or_fallback(&self, mask: u8) -> Option<&RgbaImage> {
+        self.select_variant(mask, 0)
+    }
+    /// Select a variant of the corner mask, the index wraps around the variants so any counter or random number works.
+    ///
+    /// ## Fallback
+    ///
+    /// All selection paths, including [`GridCornerAtlas::get_corner_random`] and the map renderers, share one policy
+    /// when the variants of a mask are missing, for example the atlas was deserialized without its cells:
+    ///
+    /// 1. the variant `index % count` of the mask
+    /// 2. the first cell of the mask
+    /// 3. the first cell of the full mask `0b1111`, the base terrain
+    /// 4. the placeholder set by [`GridCornerAtlas::set_fallback`]
+    ///
+    /// Returns `None` if none of them exists, masks out of range skip to the placeholder.
+    pub fn select_variant(&self, mask: u8, index: u32) -> Option<&RgbaImage> {
+        if mask < 16 {
+            let count = self.count[mask as usize].max(1) as u32;
+            let found = self
+                .loaded_variant(mask, (index % count) as u8)
+                .or_else(|| self.loaded_variant(mask, 0))
+                .or_else(|| self.loaded_variant(0b1111, 0));
+            if found.is_some() {
+                return found;
+            }
+        }
+        self.fallback.as_deref()
+    }
+    /// Select a random variant of the corner pattern, see [`GridCornerAtlas::select_variant`] for the fallback policy.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// # fn pick(atlas: &GridCornerAtlas, rng: &mut impl rand_core::RngCore) {
+    /// let cell = atlas.get_corner_random(true, true, true, true, rng);
+    /// # }
+    /// ```
+    pub fn get_corner_random<R>(&self, lu: bool, ru: bool, ld: bool, rd: bool, rng: &mut R) -> Option<&RgbaImage>
+    where
+        R: RngCore,
+    {
+        self.select_variant(grid_corner_mask(lu, ru, ld, rd), rng.next_u32())
+    }
+    fn loaded_variant(&self, mask: u8, index: u8) -> Option<&RgbaImage> {
+        if index >= self.count[mask as usize] {
+            return None;
+        }
+        self.cache.get(self.cell_index(mask, index)).map(|cell| cell.as_ref())
+    }
+    /// Get the variant or animation frame of the corner mask.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`, or the index is not less than the count of the mask.
+    pub fn get_corner_variant(&self, mask: u8, index: u8) -> &RgbaImage {
+        self.get_corner_shared(mask, index)
+    }
+    /// Get the shared handle of a variant, cells merged by [`GridCornerAtlas::dedup`] point to the same allocation.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`, or the index is not less than the count of the mask.
+    pub fn get_corner_shared(&self, mask: u8, index: u8) -> &Arc<RgbaImage> {
+        assert!(index < self.count[mask as usize], "variant {} of mask 0b{:04b} does not exist", index, mask);
+        &self.cache[self.cell_index(mask, index)]
+    }
+    /// Get the number of variants or animation frames of the corner mask.
+    pub fn get_count(&self, mask: u8) -> u8 {
+        self.count[mask as usize]
+    }
+    /// Get the duration of each animation frame, zero if the variants are not animated.
+    pub fn get_frame_duration(&self) -> Duration {
+        Duration::from_millis(self.frame_ms as u64)
+    }
+    /// Get the masks that have any visible pixel in one of their cells, bit `i` is set if mask `i` is authored.
+    ///
+    /// A fully transparent cell counts as absent, so `0xFFFF` means every mask has art.
+    pub fn authored_masks(&self) -> u16 {
+        let mut authored = 0;
+        for mask in 0..16u8 {
+            let start = self.cell_index(mask, 0);
+            let end = start + self.count[mask as usize] as usize;
+            let cells = self.cache.get(start..end).unwrap_or_default();
+            if cells.iter().any(|cell| cell.pixels().any(|p| p[3] != 0)) {
+                authored |= 1 << mask;
+            }
+        }
+        authored
+    }
+    /// Get the two terrain colors, the most common opaque color of the interior cells (mask `0b1111`) followed by that
+    /// of the exterior cells (mask `0b0000`), all variants of the mask are sampled.
+    ///
+    /// Transparent pixels are ignored, a mask without any opaque pixel reports a transparent color.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// let [inner, outer] = atlas.dominant_colors();
+    /// ```
+    pub fn dominant_colors(&self) -> [Rgba<u8>; 2] {
+        [self.dominant_color(0b1111), self.dominant_color(0b0000)]
+    }
+    fn dominant_color(&self, mask: u8) -> Rgba<u8> {
+        let start = self.cell_index(mask, 0);
+        let end = start + self.count[mask as usize] as usize;
+        let mut histogram = BTreeMap::<[u8; 4], usize>::new();
+        for cell in self.cache.get(start..end).unwrap_or_default() {
+            for pixel in cell.pixels().filter(|p| p[3] != 0) {
+                *histogram.entry(pixel.0).or_default() += 1;
+            }
+        }
+        // ties go to the smallest color so the result does not depend on the iteration order
+        let mut best = ([0; 4], 0);
+        for (color, count) in histogram {
+            if count > best.1 {
+                best = (color, count);
+            }
+        }
+        Rgba(best.0)
+    }
+    /// Get the solid quadrants of the corner mask, the bits follow the corner mask: lu, ru, ld, rd.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`.
+    pub fn collision_for(&self, mask: u8) -> u8 {
+        self.collision[mask as usize]
+    }
+    /// Get the gameplay tags of the corner mask, empty if none were added or the mask is not in range `0b0000..=0b1111`.
+    pub fn tags_for(&self, mask: u8) -> &[String] {
+        self.tags.get(&mask).map(|t| t.as_slice()).unwrap_or_default()
+    }
+    /// Get the opacity of the corner mask, in range `[0, 1]`.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the mask is not in range `0b0000..=0b1111`.
+    pub fn get_mask_opacity(&self, mask: u8) -> f32 {
+        self.opacity[mask as usize] as f32 / 255.0
+    }
+    pub(crate) fn cell_index(&self, mask: u8, index: u8) -> usize {
+        self.count[..mask as usize].iter().map(|c| *c as usize).sum::<usize>() + index as usize
+    }
     pub fn load_image(&self, root: &Path) -> ImageResult<RgbaImage> {
-        Ok(image::open(root.join(&self.key))?.to_rgba8())
+        open_image(root.join(&self.key))
     }
     pub fn load_corner(&self, root: &Path, mask: u32, index: u32) -> ImageResult<RgbaImage> {
+        self.view_corner(&self.load_image(root)?, mask, index)
+    }
+    pub(crate) fn view_corner(&self, image: &RgbaImage, mask: u32, index: u32) -> ImageResult<RgbaImage> {
         match self.count.get(mask as usize) {
             Some(s) if s.saturating_sub(1) >= index as u8 => {}
             _ => dimension_error()?,
         }
-        let image = self.load_image(root)?;
+        if (mask + 1) * self.cell_w > image.width() || (index + 1) * self.cell_h > image.height() {
+            dimension_error()?
+        }
         Ok(image.view(mask * self.cell_w, index * self.cell_h, self.cell_w, self.cell_h).to_image())
     }
 }
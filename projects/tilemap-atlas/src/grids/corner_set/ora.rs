@@ -0,0 +1,87 @@
+use super::*;
+use image::ImageOutputFormat;
+use std::{
+    io,
+    io::{Cursor, Write},
+};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+impl GridCornerAtlas {
+    /// Save the atlas as an OpenRaster file with every cell on its own layer, remember you need add `.ora` suffix.
+    ///
+    /// The canvas is the atlas in standard form, the layers are named after the mask such as `0b0101`, variants get the
+    /// index as suffix such as `0b0101-1`, so the file can be edited corner by corner in Krita or GIMP.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// atlas.export_ora("assets/grass-std.ora").unwrap();
+    /// ```
+    pub fn export_ora<P>(&self, path: P) -> ImageResult<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        if let Some(s) = path.parent() {
+            std::fs::create_dir_all(s)?
+        }
+        Ok(std::fs::write(path, self.to_ora()?)?)
+    }
+    /// Pack the atlas into the bytes of an OpenRaster file, see [`GridCornerAtlas::export_ora`].
+    pub fn to_ora(&self) -> ImageResult<Vec<u8>> {
+        let merged = self.to_image();
+        let mut zip = ZipWriter::new(Cursor::new(vec![]));
+        // the mimetype must be the first entry and stored without compression
+        add_stored(&mut zip, "mimetype", b"image/openraster")?;
+        let mut stack = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<image version=\"0.0.3\" w=\"{}\" h=\"{}\">\n<stack>\n",
+            merged.width(),
+            merged.height()
+        );
+        let mut layers = vec![];
+        for mask in 0..16u8 {
+            for index in 0..self.count[mask as usize] {
+                let name = match index {
+                    0 => format!("0b{:04b}", mask),
+                    _ => format!("0b{:04b}-{}", mask, index),
+                };
+                let (x, y) = (mask as u32 * self.cell_w, index as u32 * self.cell_h);
+                stack.push_str(&format!("<layer name=\"{name}\" src=\"data/{name}.png\" x=\"{x}\" y=\"{y}\" />\n"));
+                layers.push((name, encode_png(self.get_corner_variant(mask, index))?));
+            }
+        }
+        stack.push_str("</stack>\n</image>\n");
+        add_stored(&mut zip, "stack.xml", stack.as_bytes())?;
+        for (name, png) in &layers {
+            add_stored(&mut zip, &format!("data/{}.png", name), png)?;
+        }
+        add_stored(&mut zip, "mergedimage.png", &encode_png(&merged)?)?;
+        let thumbnail = match merged.width().max(merged.height()) {
+            s if s > 256 => {
+                let scale = 256.0 / s as f32;
+                let w = ((merged.width() as f32 * scale) as u32).max(1);
+                let h = ((merged.height() as f32 * scale) as u32).max(1);
+                image::imageops::thumbnail(&merged, w, h)
+            }
+            _ => merged,
+        };
+        add_stored(&mut zip, "Thumbnails/thumbnail.png", &encode_png(&thumbnail)?)?;
+        Ok(zip.finish().map_err(io::Error::from)?.into_inner())
+    }
+}
+
+fn encode_png(image: &RgbaImage) -> ImageResult<Vec<u8>> {
+    let mut bytes = Cursor::new(vec![]);
+    image.write_to(&mut bytes, ImageOutputFormat::Png)?;
+    Ok(bytes.into_inner())
+}
+
+/// Add an entry without compression, which is all OpenRaster needs since the layers are png already.
+fn add_stored(zip: &mut ZipWriter<Cursor<Vec<u8>>>, name: &str, data: &[u8]) -> ImageResult<()> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file(name, options).map_err(io::Error::from)?;
+    zip.write_all(data)?;
+    Ok(())
+}
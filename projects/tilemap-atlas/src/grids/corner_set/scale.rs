@@ -0,0 +1,204 @@
+use super::*;
+use image::{imageops::FilterType, Rgba, Rgba32FImage};
+
+impl GridCornerAtlas {
+    /// Upscale every cell with an edge-directed pixel-art scaler, sharper than bilinear and smoother than nearest.
+    ///
+    /// The scaler is the Scale2x / Scale3x variant of EPX (AdvMAME2x / AdvMAME3x), `4` applies Scale2x twice. Each cell
+    /// is scaled on its own and samples outside the cell are clamped to the cell border, so seams between cells stay
+    /// intact.
+    ///
+    /// # Arguments
+    ///
+    /// * `scale`: The scale factor, one of `1`, `2`, `3` or `4`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// atlas.upscale_hq(2).unwrap().save("assets/grass-std@2x.png").unwrap();
+    /// ```
+    pub fn upscale_hq(&self, scale: u32) -> ImageResult<Self> {
+        let upscale: fn(&RgbaImage) -> RgbaImage = match scale {
+            1 => RgbaImage::clone,
+            2 => scale2x,
+            3 => scale3x,
+            4 => |cell| scale2x(&scale2x(cell)),
+            _ => io_error(format!("The scale factor {} is not one of 1, 2, 3 or 4", scale), ErrorKind::InvalidInput)?,
+        };
+        Ok(self.map_cells(self.cell_w * scale, self.cell_h * scale, upscale))
+    }
+    /// Check that the cells can be scaled to the target width without resampling, and return the scale factor.
+    ///
+    /// The target must be an integer multiple or an integer divisor of the cell width, the factor is `target / cell`
+    /// when scaling up and `cell / target` when scaling down. The height is scaled by the same factor, so it must be
+    /// divisible by the factor too when scaling down.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// let scale = atlas.assert_integer_scalable(48).unwrap();
+    /// ```
+    pub fn assert_integer_scalable(&self, target: u32) -> ImageResult<u32> {
+        integer_scale(self.cell_w, self.cell_h, target)
+    }
+    /// Resize every cell to the given size, the alpha is premultiplied during the resize so translucent edges do not
+    /// darken.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use image::imageops::FilterType;
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// atlas.resized(16, 16, FilterType::Triangle).unwrap().save("assets/grass-std@16.png").unwrap();
+    /// ```
+    pub fn resized(&self, cell_w: u32, cell_h: u32, filter: FilterType) -> ImageResult<Self> {
+        self.resized_with(cell_w, cell_h, filter, true)
+    }
+    /// Resize every cell to the given size, see [`GridCornerAtlas::resized`].
+    ///
+    /// # Arguments
+    ///
+    /// * `correct_alpha`: Premultiply the alpha before scaling and restore it after, otherwise the color of fully
+    ///   transparent pixels bleeds into their neighbors
+    pub fn resized_with(&self, cell_w: u32, cell_h: u32, filter: FilterType, correct_alpha: bool) -> ImageResult<Self> {
+        if cell_w == 0 || cell_h == 0 {
+            io_error("The cell width and height must be greater than zero", ErrorKind::InvalidInput)?;
+        }
+        Ok(self.map_cells(cell_w, cell_h, |cell| match correct_alpha {
+            true => resize_premultiplied(cell, cell_w, cell_h, filter),
+            false => image::imageops::resize(cell, cell_w, cell_h, filter),
+        }))
+    }
+    /// Apply the same transform to the cached cells and the authored cells kept by faded masks.
+    fn map_cells<F>(&self, cell_w: u32, cell_h: u32, f: F) -> Self
+    where
+        F: Fn(&RgbaImage) -> RgbaImage,
+    {
+        let cache = self.cache.iter().map(|cell| Arc::new(f(cell))).collect();
+        let authored =
+            self.authored.iter().map(|(mask, cells)| (*mask, cells.iter().map(|cell| Arc::new(f(cell))).collect())).collect();
+        Self { cell_w, cell_h, cache, authored, ..self.clone() }
+    }
+}
+
+/// The scale factor of [`GridCornerAtlas::assert_integer_scalable`], for any cell size.
+pub(crate) fn integer_scale(cell_w: u32, cell_h: u32, target: u32) -> ImageResult<u32> {
+    if cell_w == 0 || target == 0 {
+        io_error("The cell width and the target must be greater than zero", ErrorKind::InvalidInput)?;
+    }
+    if target >= cell_w && target % cell_w == 0 {
+        return Ok(target / cell_w);
+    }
+    if target < cell_w && cell_w % target == 0 && cell_h % (cell_w / target) == 0 {
+        return Ok(cell_w / target);
+    }
+    io_error(
+        format!("The target {}px is not an integer multiple or divisor of the {}x{} cell", target, cell_w, cell_h),
+        ErrorKind::InvalidInput,
+    )
+}
+
+fn resize_premultiplied(image: &RgbaImage, w: u32, h: u32, filter: FilterType) -> RgbaImage {
+    let premultiplied = Rgba32FImage::from_fn(image.width(), image.height(), |x, y| {
+        let Rgba([r, g, b, a]) = *image.get_pixel(x, y);
+        let a = a as f32 / 255.0;
+        Rgba([r as f32 / 255.0 * a, g as f32 / 255.0 * a, b as f32 / 255.0 * a, a])
+    });
+    let resized = image::imageops::resize(&premultiplied, w, h, filter);
+    RgbaImage::from_fn(w, h, |x, y| {
+        let Rgba([r, g, b, a]) = *resized.get_pixel(x, y);
+        let a = a.clamp(0.0, 1.0);
+        let unpremultiply = |c: f32| if a > 0.0 { (c / a * 255.0).round().clamp(0.0, 255.0) as u8 } else { 0 };
+        Rgba([unpremultiply(r), unpremultiply(g), unpremultiply(b), (a * 255.0).round() as u8])
+    })
+}
+
+/// Sample the pixel with the coordinates clamped to the image border.
+fn clamped(image: &RgbaImage, x: i64, y: i64) -> Rgba<u8> {
+    let x = x.clamp(0, image.width() as i64 - 1) as u32;
+    let y = y.clamp(0, image.height() as i64 - 1) as u32;
+    *image.get_pixel(x, y)
+}
+
+fn scale2x(image: &RgbaImage) -> RgbaImage {
+    let mut output = RgbaImage::new(image.width() * 2, image.height() * 2);
+    for (x, y, e) in image.enumerate_pixels() {
+        let (i, j) = (x as i64, y as i64);
+        let b = clamped(image, i, j - 1);
+        let d = clamped(image, i - 1, j);
+        let f = clamped(image, i + 1, j);
+        let h = clamped(image, i, j + 1);
+        let e = *e;
+        let mut out = [e; 4];
+        if b != h && d != f {
+            if d == b {
+                out[0] = d
+            }
+            if b == f {
+                out[1] = f
+            }
+            if d == h {
+                out[2] = d
+            }
+            if h == f {
+                out[3] = f
+            }
+        }
+        for (k, pixel) in out.into_iter().enumerate() {
+            output.put_pixel(x * 2 + k as u32 % 2, y * 2 + k as u32 / 2, pixel);
+        }
+    }
+    output
+}
+
+fn scale3x(image: &RgbaImage) -> RgbaImage {
+    let mut output = RgbaImage::new(image.width() * 3, image.height() * 3);
+    for (x, y, e) in image.enumerate_pixels() {
+        let (i, j) = (x as i64, y as i64);
+        let a = clamped(image, i - 1, j - 1);
+        let b = clamped(image, i, j - 1);
+        let c = clamped(image, i + 1, j - 1);
+        let d = clamped(image, i - 1, j);
+        let f = clamped(image, i + 1, j);
+        let g = clamped(image, i - 1, j + 1);
+        let h = clamped(image, i, j + 1);
+        let k = clamped(image, i + 1, j + 1);
+        let e = *e;
+        let mut out = [e; 9];
+        if b != h && d != f {
+            if d == b {
+                out[0] = d
+            }
+            if (d == b && e != c) || (b == f && e != a) {
+                out[1] = b
+            }
+            if b == f {
+                out[2] = f
+            }
+            if (d == b && e != g) || (d == h && e != a) {
+                out[3] = d
+            }
+            if (b == f && e != k) || (h == f && e != c) {
+                out[5] = f
+            }
+            if d == h {
+                out[6] = d
+            }
+            if (d == h && e != k) || (h == f && e != g) {
+                out[7] = h
+            }
+            if h == f {
+                out[8] = f
+            }
+        }
+        for (n, pixel) in out.into_iter().enumerate() {
+            output.put_pixel(x * 3 + n as u32 % 3, y * 3 + n as u32 / 3, pixel);
+        }
+    }
+    output
+}
@@ -0,0 +1,61 @@
+use super::*;
+use image::{GrayImage, Luma};
+
+impl GridCornerAtlas {
+    /// Convert the atlas in standard form to a signed distance field of the alpha edge, for SDF tile shaders.
+    ///
+    /// A pixel is inside if its alpha is at least `128`. The distance is stored as `128 + 127 * d / spread`, so values
+    /// above `128` are inside and values below are outside, distances beyond the spread are clamped. Every cell is
+    /// computed on its own, the edges of the neighbors never bleed in.
+    ///
+    /// # Arguments
+    ///
+    /// * `spread`: The distance in pixels that maps to the full range, at least `1`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+    /// atlas.to_sdf_atlas(4).save("assets/grass-sdf.png").unwrap();
+    /// ```
+    pub fn to_sdf_atlas(&self, spread: u32) -> GrayImage {
+        let spread = spread.max(1);
+        let rows = self.count.iter().copied().max().unwrap_or(1) as u32;
+        let mut output = GrayImage::new(self.cell_w * 16, self.cell_h * rows);
+        for mask in 0..16u8 {
+            for index in 0..self.count[mask as usize] {
+                let field = cell_sdf(self.get_corner_variant(mask, index), spread);
+                image::imageops::replace(
+                    &mut output,
+                    &field,
+                    (mask as u32 * self.cell_w) as i64,
+                    (index as u32 * self.cell_h) as i64,
+                );
+            }
+        }
+        output
+    }
+}
+
+/// Search the nearest pixel on the other side of the edge within the spread, the edge lies half a pixel before it.
+fn cell_sdf(cell: &RgbaImage, spread: u32) -> GrayImage {
+    let (w, h) = cell.dimensions();
+    let inside = |x: u32, y: u32| cell.get_pixel(x, y)[3] >= 128;
+    let r = spread as i64;
+    GrayImage::from_fn(w, h, |x, y| {
+        let here = inside(x, y);
+        let mut nearest = spread as f64;
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let (sx, sy) = (x as i64 + dx, y as i64 + dy);
+                if sx < 0 || sy < 0 || sx >= w as i64 || sy >= h as i64 || inside(sx as u32, sy as u32) == here {
+                    continue;
+                }
+                nearest = nearest.min(((dx * dx + dy * dy) as f64).sqrt() - 0.5);
+            }
+        }
+        let d = if here { nearest } else { -nearest };
+        Luma([(128.0 + 127.0 * d / spread as f64).round().clamp(0.0, 255.0) as u8])
+    })
+}
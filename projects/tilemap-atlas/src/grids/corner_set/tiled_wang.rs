@@ -0,0 +1,72 @@
+use super::*;
+use ::tiled::{Map, WangId, WangSetType};
+
+impl GridCornerAtlas {
+    /// Create a corner atlas from a wangset of a map loaded by the [`tiled`](https://docs.rs/tiled) crate.
+    ///
+    /// The wangset must have two colors, the first color is the ground and the second color covers the corner. Tiles
+    /// with the same corners become variants in tile id order, and every mask must have at least one tile.
+    ///
+    /// Corner wangsets and mixed wangsets without edge colors are supported, edge wangsets describe sides rather than
+    /// corners and are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let map = tiled::Loader::new().load_tmx_map("assets/map.tmx").unwrap();
+    /// let atlas = GridCornerAtlas::from_tiled_wangset(&map, "grass").unwrap();
+    /// ```
+    pub fn from_tiled_wangset(map: &Map, wangset_name: &str) -> ImageResult<Self> {
+        let found = map
+            .tilesets()
+            .iter()
+            .find_map(|tileset| Some((tileset, tileset.wang_sets.iter().find(|w| w.name == wangset_name)?)));
+        let (tileset, wangset) = match found {
+            Some(s) => s,
+            None => io_error(format!("The wangset {} does not exist", wangset_name), ErrorKind::NotFound)?,
+        };
+        let has_edges = |id: &WangId| id.0.iter().step_by(2).any(|c| *c != 0);
+        match wangset.wang_set_type {
+            WangSetType::Corner => {}
+            WangSetType::Mixed if !wangset.wang_tiles.values().any(|t| has_edges(&t.wang_id)) => {}
+            _ => io_error(
+                format!("The wangset {} uses edge colors, only corner wangsets map to corner masks", wangset_name),
+                ErrorKind::Unsupported,
+            )?,
+        }
+        if wangset.wang_colors.len() != 2 {
+            io_error(format!("The wangset {} must have exactly 2 colors", wangset_name), ErrorKind::InvalidInput)?
+        }
+        let image = match &tileset.image {
+            Some(s) => open_image(&s.source)?,
+            None => io_error(format!("The tileset {} is not a single image tileset", tileset.name), ErrorKind::Unsupported)?,
+        };
+        let (cell_w, cell_h) = (tileset.tile_width, tileset.tile_height);
+        let mut masks: Vec<Vec<u32>> = vec![vec![]; 16];
+        for (id, tile) in wangset.wang_tiles.iter() {
+            // the corners are stored as top right, bottom right, bottom left, top left
+            let [_, ru, _, rd, _, ld, _, lu] = tile.wang_id.0.map(|c| c == 2);
+            masks[grid_corner_mask(lu, ru, ld, rd) as usize].push(*id);
+        }
+        let mut count = [0; 16];
+        let mut cache = vec![];
+        for (mask, ids) in masks.iter_mut().enumerate() {
+            ids.sort_unstable();
+            if ids.is_empty() {
+                io_error(format!("The wangset {} has no tile for mask 0b{:04b}", wangset_name, mask), ErrorKind::InvalidInput)?
+            }
+            count[mask] = ids.len().min(255) as u8;
+            for id in ids.iter().take(255) {
+                let columns = tileset.columns.max(1);
+                let x = tileset.margin + (id % columns) * (cell_w + tileset.spacing);
+                let y = tileset.margin + (id / columns) * (cell_h + tileset.spacing);
+                if x + cell_w > image.width() || y + cell_h > image.height() {
+                    dimension_error()?
+                }
+                cache.push(Arc::new(image.view(x, y, cell_w, cell_h).to_image()));
+            }
+        }
+        Ok(Self { cell_w, cell_h, count, cache, ..Default::default() })
+    }
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+/// The cells of a [`GridCornerAtlas`] with their transparent margins cut off, see [`GridCornerAtlas::to_trimmed`]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrimmedAtlas {
+    /// The trimmed cells packed row by row, at most as wide as the atlas in standard form
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub image: RgbaImage,
+    /// The placement of every cell, ordered by mask then variant
+    pub frames: Vec<TrimmedFrame>,
+}
+
+/// A trimmed cell in a [`TrimmedAtlas`], with everything needed to put it back at its original place
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TrimmedFrame {
+    /// The corner mask, in range `0b0000..=0b1111`
+    pub mask: u8,
+    /// The variant of the mask
+    pub index: u8,
+    /// The pixel rect of the trimmed cell in the packed image, `(x, y, w, h)`, empty if the cell is fully transparent
+    pub rect: (u32, u32, u32, u32),
+    /// The position of the trimmed pixels in the untrimmed cell
+    pub offset: (u32, u32),
+    /// The size of the untrimmed cell
+    pub source_size: (u32, u32),
+    /// The pivot relative to the untrimmed cell, `(0, 0)` is the top left corner and `(1, 1)` the bottom right corner
+    pub pivot: (f32, f32),
+    /// The same pivot relative to the trimmed rect, outside `[0, 1]` if the pivot was trimmed away
+    pub trimmed_pivot: (f32, f32),
+}
+
+impl GridCornerAtlas {
+    /// Cut the transparent margins off every cell and pack the cells row by row, recording the trim offset and the pivot
+    /// of each cell so engines can place the trimmed sprites exactly where the full cells were.
+    ///
+    /// Large decorative cells often cover only a small part of the tile, trimming them saves most of the texture.
+    ///
+    /// # Arguments
+    ///
+    /// * `pivot`: The anchor of the untrimmed cells, such as `(0.5, 1.0)` for the bottom center
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerAtlas;
+    /// let atlas = GridCornerAtlas::load("assets/flowers-std.png").unwrap();
+    /// let trimmed = atlas.to_trimmed((0.5, 1.0));
+    /// trimmed.image.save("assets/flowers-trimmed.png").unwrap();
+    /// ```
+    pub fn to_trimmed(&self, pivot: (f32, f32)) -> TrimmedAtlas {
+        let max_w = self.cell_w * 16;
+        let mut cuts = vec![];
+        for mask in 0..16u8 {
+            for index in 0..self.count[mask as usize] {
+                let cell = self.get_corner_variant(mask, index);
+                cuts.push((mask, index, opaque_bounds(cell)));
+            }
+        }
+        // shelf packing, a new row starts when the cell does not fit in the current one
+        let mut frames = Vec::with_capacity(cuts.len());
+        let (mut x, mut y, mut shelf, mut width) = (0, 0, 0, 0);
+        for (mask, index, (ox, oy, w, h)) in &cuts {
+            if x + w > max_w {
+                (x, y, shelf) = (0, y + shelf, 0);
+            }
+            let (px, py) = (pivot.0 * self.cell_w as f32, pivot.1 * self.cell_h as f32);
+            let trimmed_pivot = match (*w, *h) {
+                (0, _) | (_, 0) => pivot,
+                (w, h) => ((px - *ox as f32) / w as f32, (py - *oy as f32) / h as f32),
+            };
+            frames.push(TrimmedFrame {
+                mask: *mask,
+                index: *index,
+                rect: (x, y, *w, *h),
+                offset: (*ox, *oy),
+                source_size: (self.cell_w, self.cell_h),
+                pivot,
+                trimmed_pivot,
+            });
+            x += w;
+            shelf = shelf.max(*h);
+            width = width.max(x);
+        }
+        let mut image = RgbaImage::new(width, y + shelf);
+        for frame in &frames {
+            let (x, y, w, h) = frame.rect;
+            if w != 0 && h != 0 {
+                let cell = self.get_corner_variant(frame.mask, frame.index);
+                let view = cell.view(frame.offset.0, frame.offset.1, w, h).to_image();
+                image::imageops::replace(&mut image, &view, x as i64, y as i64);
+            }
+        }
+        TrimmedAtlas { image, frames }
+    }
+}
+
+impl TrimmedAtlas {
+    /// Put the trimmed pixels of the frame back into a cell of the original size.
+    pub fn untrim(&self, frame: &TrimmedFrame) -> RgbaImage {
+        let (x, y, w, h) = frame.rect;
+        let mut cell = RgbaImage::new(frame.source_size.0, frame.source_size.1);
+        if w != 0 && h != 0 {
+            let view = self.image.view(x, y, w, h).to_image();
+            image::imageops::replace(&mut cell, &view, frame.offset.0 as i64, frame.offset.1 as i64);
+        }
+        cell
+    }
+}
+
+/// The smallest rect `(x, y, w, h)` holding every visible pixel, empty for a fully transparent cell.
+fn opaque_bounds(cell: &RgbaImage) -> (u32, u32, u32, u32) {
+    let (mut x0, mut y0, mut x1, mut y1) = (u32::MAX, u32::MAX, 0, 0);
+    for (x, y, pixel) in cell.enumerate_pixels() {
+        if pixel[3] != 0 {
+            (x0, y0, x1, y1) = (x0.min(x), y0.min(y), x1.max(x + 1), y1.max(y + 1));
+        }
+    }
+    match x0 {
+        u32::MAX => (0, 0, 0, 0),
+        _ => (x0, y0, x1 - x0, y1 - y0),
+    }
+}
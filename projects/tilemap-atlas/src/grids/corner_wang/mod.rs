@@ -17,17 +17,17 @@ impl GridCornerWang {
     {
         Self { key: key.to_string(), cell_w: width, cell_h: height }
     }
-    pub fn as_standard<S, G>(&self, name: &str, image: &RgbaImage) -> ImageResult<(GridCornerAtlas, RgbaImage)>
-    where
-        S: ToString,
-        G: GenericImageView,
-    {
+    pub fn as_standard(&self, name: &str, image: &RgbaImage) -> ImageResult<(GridCornerAtlas, RgbaImage)> {
         let mut output = RgbaImage::new(self.cell_w * 16, self.cell_h);
+        let mut cache = Vec::with_capacity(16);
         for i in 0..16 {
             let view = view_wang4x4c_cell(image, i as u8);
             output.copy_from(&*view, i * self.cell_w, 0)?;
+            cache.push(Arc::new(view.to_image()));
         }
-        Ok((GridCornerAtlas { key: name.to_string(), cell_w: self.cell_w, cell_h: self.cell_h, count: [1; 16] }, output))
+        let atlas =
+            GridCornerAtlas { key: name.to_string(), cell_w: self.cell_w, cell_h: self.cell_h, cache, ..Default::default() };
+        Ok((atlas, output))
     }
 }
 
@@ -49,6 +49,10 @@ impl GridCornerWang {
     pub fn get_key(&self) -> &str {
         &self.key
     }
+    /// Get the width and height of a single cell.
+    pub fn get_cell_size(&self) -> (u32, u32) {
+        (self.cell_w, self.cell_h)
+    }
     /// Get Image
     ///
     /// # Arguments
@@ -79,7 +83,7 @@ impl GridCornerWang {
     /// # use tileset::GridCornerWang;
     /// ```
     pub fn get_image(&self, root: &Path) -> ImageResult<RgbaImage> {
-        Ok(image::open(self.get_path(root))?.to_rgba8())
+        open_image(self.get_path(root))
     }
     /// Get Image
     ///
@@ -101,7 +105,10 @@ impl GridCornerWang {
     pub fn load_corner(&self, root: &Path, mask: u8) -> ImageResult<RgbaImage> {
         debug_assert!(mask >= 16, "corner mask {} is not in range [0b0000, 0b1111]", mask);
         let image = self.get_image(root)?;
-        Ok(view_wang4x4c_cell(&image, mask).to_image())
+        Ok(self.view_corner(&image, mask))
+    }
+    pub(crate) fn view_corner(&self, image: &RgbaImage, mask: u8) -> RgbaImage {
+        view_wang4x4c_cell(image, mask).to_image()
     }
 }
 
@@ -132,26 +139,32 @@ impl GridCornerWang {
 /// 0b1110 <- 7  <- (2, 2)
 /// 0b1111 <- 15 <- (3, 2)
 /// ```
-fn view_wang4x4c_cell(r: &RgbaImage, mask: u8) -> SubImage<&RgbaImage> {
+pub(crate) fn view_wang4x4c_cell(r: &RgbaImage, mask: u8) -> SubImage<&RgbaImage> {
     let w = r.width() / 4;
     let h = r.height() / 4;
+    let (x, y) = wang4x4c_position(mask);
+    r.view(x * w, y * h, w, h)
+}
+
+/// Get the cell position `(column, row)` of the mask in a 4x4 wang corner sheet
+pub(crate) fn wang4x4c_position(mask: u8) -> (u32, u32) {
     match mask {
-        0b0000 => r.view(0 * w, 3 * h, w, h),
-        0b0001 => r.view(3 * w, 3 * h, w, h),
-        0b0010 => r.view(0 * w, 2 * h, w, h),
-        0b0011 => r.view(1 * w, 2 * h, w, h),
-        0b0100 => r.view(0 * w, 0 * h, w, h),
-        0b0101 => r.view(3 * w, 2 * h, w, h),
-        0b0110 => r.view(2 * w, 3 * h, w, h),
-        0b0111 => r.view(3 * w, 1 * h, w, h),
-        0b1000 => r.view(1 * w, 3 * h, w, h),
-        0b1001 => r.view(0 * w, 1 * h, w, h),
-        0b1010 => r.view(1 * w, 0 * h, w, h),
-        0b1011 => r.view(2 * w, 2 * h, w, h),
-        0b1100 => r.view(3 * w, 0 * h, w, h),
-        0b1101 => r.view(2 * w, 0 * h, w, h),
-        0b1110 => r.view(1 * w, 1 * h, w, h),
-        0b1111 => r.view(2 * w, 1 * h, w, h),
+        0b0000 => (0, 3),
+        0b0001 => (3, 3),
+        0b0010 => (0, 2),
+        0b0011 => (1, 2),
+        0b0100 => (0, 0),
+        0b0101 => (3, 2),
+        0b0110 => (2, 3),
+        0b0111 => (3, 1),
+        0b1000 => (1, 3),
+        0b1001 => (0, 1),
+        0b1010 => (1, 0),
+        0b1011 => (2, 2),
+        0b1100 => (3, 0),
+        0b1101 => (2, 0),
+        0b1110 => (1, 1),
+        0b1111 => (2, 1),
         _ => unreachable!(),
     }
 }
@@ -30,4 +30,8 @@ impl GridEdgeAtlas {
     pub fn get_key(&self) -> &str {
         &self.key
     }
+    /// Get the width and height of a single cell.
+    pub fn get_cell_size(&self) -> (u32, u32) {
+        (self.cell_w, self.cell_h)
+    }
 }
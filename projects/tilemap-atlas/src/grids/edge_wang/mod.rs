@@ -17,17 +17,17 @@ impl GridEdgeWang {
     {
         Self { key: key.to_string(), cell_w: width, cell_h: height }
     }
-    pub fn as_standard<S, G>(&self, name: &str, image: &RgbaImage) -> ImageResult<(GridCornerAtlas, RgbaImage)>
-    where
-        S: ToString,
-        G: GenericImageView,
-    {
+    pub fn as_standard(&self, name: &str, image: &RgbaImage) -> ImageResult<(GridCornerAtlas, RgbaImage)> {
         let mut output = RgbaImage::new(self.cell_w * 16, self.cell_h);
+        let mut cache = Vec::with_capacity(16);
         for i in 0..16 {
             let view = view_wang4x4e_cell(image, i as u8);
             output.copy_from(&*view, i * self.cell_w, 0)?;
+            cache.push(Arc::new(view.to_image()));
         }
-        Ok((GridCornerAtlas { key: name.to_string(), cell_w: self.cell_w, cell_h: self.cell_h, count: [1; 16] }, output))
+        let atlas =
+            GridCornerAtlas { key: name.to_string(), cell_w: self.cell_w, cell_h: self.cell_h, cache, ..Default::default() };
+        Ok((atlas, output))
     }
 }
 
@@ -49,6 +49,10 @@ impl GridEdgeWang {
     pub fn get_key(&self) -> &str {
         &self.key
     }
+    /// Get the width and height of a single cell.
+    pub fn get_cell_size(&self) -> (u32, u32) {
+        (self.cell_w, self.cell_h)
+    }
     /// Get Image
     ///
     /// # Arguments
@@ -79,7 +83,7 @@ impl GridEdgeWang {
     /// # use tileset::GridCornerWang;
     /// ```
     pub fn get_image(&self, root: &Path) -> ImageResult<RgbaImage> {
-        Ok(image::open(self.get_path(root))?.to_rgba8())
+        open_image(self.get_path(root))
     }
     /// Get Image
     ///
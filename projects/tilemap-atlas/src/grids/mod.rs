@@ -7,11 +7,18 @@ pub mod edge_wang;
 pub mod rpg_maker_vx;
 pub mod rpg_maker_xp;
 
-use crate::{traits::io_error, utils::save_as_png, GridCompleteAtlas, GridCornerAtlas};
+use crate::{
+    traits::{io_error, open_image},
+    utils::save_as_png,
+    GridCompleteAtlas, GridCornerAtlas,
+};
 use image::{GenericImage, GenericImageView, ImageResult, RgbaImage, SubImage};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     io::ErrorKind,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
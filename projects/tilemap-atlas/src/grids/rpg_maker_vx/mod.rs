@@ -1,6 +1,8 @@
 use super::*;
 mod to_complete;
 
+pub(crate) use self::to_complete::{dim_unused_cells, rpg4x6_wang_quadrants};
+
 /// A corner type tile set used in [RPG Maker VX](), [RPG MakerMV](), [RPG MakerMZ]().
 ///
 /// ## Example
@@ -64,7 +66,7 @@ impl GridCornerRMVX {
     where
         P: AsRef<Path>,
     {
-        let image = image::open(path)?.to_rgba8();
+        let image = open_image(path)?;
         let (w, h) = image.dimensions();
         if w % 4 != 0 || h % 6 != 0 {
             io_error(
@@ -90,3 +92,10 @@ impl GridCornerRMVX {
         save_as_png(&self.image, path)
     }
 }
+
+/// Borrow the source image of the tile set.
+impl AsRef<RgbaImage> for GridCornerRMVX {
+    fn as_ref(&self) -> &RgbaImage {
+        &self.image
+    }
+}
@@ -1,8 +1,12 @@
 use super::*;
+use crate::traits::dimension_error;
+use image::Rgba;
 
 impl GridCornerRMVX {
     /// Returns a new `GridCompleteAtlas` from the current `GridCornerRMVX`.
     ///
+    /// Cells of the complete atlas without a source cell in the sheet are left transparent.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -18,7 +22,10 @@ impl GridCornerRMVX {
         let mut output = RgbaImage::new(w * C, h * L);
         for i in 0..C {
             for j in 0..L {
-                let (x, y) = rpg4x6_to_complete(i, j);
+                let Ok((x, y)) = rpg4x6_to_complete(i, j)
+                else {
+                    continue;
+                };
                 let view = self.image.view(x * w, y * h, w, h);
                 output.copy_from(&*view, i * w, j * h).ok();
             }
@@ -26,10 +33,89 @@ impl GridCornerRMVX {
         // SAFETY: output image definitely has the correct size
         unsafe { GridCompleteAtlas::create(output) }
     }
+    /// Returns a new standard form [`GridCornerAtlas`] from the current `GridCornerRMVX`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerRMVX;
+    /// let rpg = GridCornerRMVX::load("assets/rpg4x6.png").unwrap();
+    /// rpg.as_standard().unwrap().save("assets/rpg4x6-std.png").unwrap();
+    /// ```
+    pub fn as_standard(&self) -> ImageResult<GridCornerAtlas> {
+        self.make_standard(false)
+    }
+    /// Returns a new standard form [`GridCornerAtlas`], and cross-fade the 1px borders where the quadrants meet.
+    ///
+    /// This hides the hard seam of masks like `0b0011` whose quadrants come from different parts of the sheet, but
+    /// blurs the pixel art slightly, so [`GridCornerRMVX::as_standard`] does not do it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerRMVX;
+    /// let rpg = GridCornerRMVX::load("assets/rpg4x6.png").unwrap();
+    /// rpg.as_standard_cross_faded().unwrap().save("assets/rpg4x6-std.png").unwrap();
+    /// ```
+    pub fn as_standard_cross_faded(&self) -> ImageResult<GridCornerAtlas> {
+        self.make_standard(true)
+    }
+    /// Compose the cell of the corner mask from its four quadrants, the same cell [`GridCornerRMVX::as_standard`]
+    /// produces.
+    ///
+    /// Returns an error if the mask is not in range `0b0000..=0b1111`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerRMVX;
+    /// let rpg = GridCornerRMVX::load("assets/rpg4x6.png").unwrap();
+    /// let inner = rpg.view_corner(0b1111).unwrap();
+    /// ```
+    pub fn view_corner(&self, mask: u8) -> ImageResult<RgbaImage> {
+        rpg4x6_to_wang(&self.image, mask)
+    }
+    /// Find the cells of the sheet used by [`GridCornerRMVX::as_standard`], indexed by `[row][column]`.
+    ///
+    /// Every cell of a well formed sheet is used, see [`GridCornerRMXP::coverage`](crate::GridCornerRMXP::coverage) for a layout with spare cells.
+    pub fn coverage(&self) -> [[bool; 4]; 6] {
+        let mut used = [[false; 4]; 6];
+        for mask in 0..16 {
+            for (x, y) in rpg4x6_wang_quadrants(mask).into_iter().flatten() {
+                used[y as usize][x as usize] = true;
+            }
+        }
+        used
+    }
+    /// Dim the cells that [`GridCornerRMVX::coverage`] reports as unused, so artists can see what is safe to repurpose.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerRMVX;
+    /// let rpg = GridCornerRMVX::load("assets/rpg4x6.png").unwrap();
+    /// rpg.coverage_image().save("assets/rpg4x6-coverage.png").unwrap();
+    /// ```
+    pub fn coverage_image(&self) -> RgbaImage {
+        let used = self.coverage();
+        dim_unused_cells(&self.image, self.cell_w, self.cell_h, |x, y| used[y as usize][x as usize])
+    }
+    fn make_standard(&self, cross_fade: bool) -> ImageResult<GridCornerAtlas> {
+        let mut cache = Vec::with_capacity(16);
+        for mask in 0..16 {
+            let mut cell = rpg4x6_to_wang(&self.image, mask)?;
+            if cross_fade {
+                cross_fade_quadrants(&mut cell);
+            }
+            cache.push(Arc::new(cell));
+        }
+        Ok(GridCornerAtlas { cell_w: self.cell_w * 2, cell_h: self.cell_h * 2, cache, ..Default::default() })
+    }
 }
 
-fn rpg4x6_to_complete(x: u32, y: u32) -> (u32, u32) {
-    match (x, y) {
+/// The source cell in the sheet of the cell `(x, y)` of the complete atlas, only part of the atlas is covered.
+fn rpg4x6_to_complete(x: u32, y: u32) -> ImageResult<(u32, u32)> {
+    let cell = match (x, y) {
         //
         (0, 0) => (0, 2),
         (0, 1) => (0, 3),
@@ -246,8 +332,9 @@ fn rpg4x6_to_complete(x: u32, y: u32) -> (u32, u32) {
         (23, 5) => (3, 3),
         (23, 6) => (3, 4),
         (23, 7) => (3, 5),
-        _ => unreachable!(),
-    }
+        _ => dimension_error()?,
+    };
+    Ok(cell)
 }
 
 /// ```js
@@ -268,11 +355,22 @@ fn rpg4x6_to_complete(x: u32, y: u32) -> (u32, u32) {
 /// 0b1110 <- [(1, 1), (2, 1), (3, 4), (3, 1)]
 /// 0b1111 <- [(2, 4), (3, 4), (4, 3), (3, 5)]
 /// ```
-#[allow(unused)]
 fn rpg4x6_to_wang(raw: &RgbaImage, mask: u8) -> ImageResult<RgbaImage> {
     let width = raw.width() / 4;
     let height = raw.height() / 6;
-    let xs = match mask {
+    let mut out = RgbaImage::new(width * 2, height * 2);
+    for (i, (x, y)) in rpg4x6_wang_quadrants(mask)?.iter().enumerate() {
+        let view = raw.view(*x * width, *y * height, width, height);
+        let x = (i as u32 % 2) * width;
+        let y = (i as u32 / 2) * height;
+        out.copy_from(&view.to_image(), x, y)?;
+    }
+    Ok(out)
+}
+
+/// The source cells of the four quadrants of the mask, in the order lu, ru, ld, rd.
+pub(crate) fn rpg4x6_wang_quadrants(mask: u8) -> ImageResult<[(u32, u32); 4]> {
+    let cells = match mask {
         0b0000 => [(0, 0), (1, 0), (0, 1), (1, 1)],
         0b0001 => [(3, 5), (1, 0), (0, 1), (1, 1)],
         0b0010 => [(0, 0), (0, 5), (0, 1), (1, 1)],
@@ -289,14 +387,42 @@ fn rpg4x6_to_wang(raw: &RgbaImage, mask: u8) -> ImageResult<RgbaImage> {
         0b1101 => [(3, 3), (1, 0), (3, 0), (2, 2)],
         0b1110 => [(0, 0), (0, 3), (1, 2), (2, 0)],
         0b1111 => [(1, 3), (2, 3), (1, 4), (2, 4)],
-        _ => unreachable!(),
+        _ => dimension_error()?,
     };
-    let mut out = RgbaImage::new(width * 2, height * 2);
-    for (i, (x, y)) in xs.iter().enumerate() {
-        let view = raw.view(*x * width, *y * height, width, height);
-        let x = (i as u32 % 2) * width;
-        let y = (i as u32 / 2) * height;
-        out.copy_from(&view.to_image(), x, y)?;
+    Ok(cells)
+}
+
+/// Keep the used cells and darken the color of the others to a quarter.
+pub(crate) fn dim_unused_cells<F>(image: &RgbaImage, cell_w: u32, cell_h: u32, used: F) -> RgbaImage
+where
+    F: Fn(u32, u32) -> bool,
+{
+    let mut output = image.clone();
+    for (x, y, pixel) in output.enumerate_pixels_mut() {
+        if !used(x / cell_w, y / cell_h) {
+            let Rgba([r, g, b, a]) = *pixel;
+            *pixel = Rgba([r / 4, g / 4, b / 4, a]);
+        }
+    }
+    output
+}
+
+/// Mix the pixels on both sides of the internal quadrant borders, 3 parts of itself and 1 part of its neighbor.
+fn cross_fade_quadrants(cell: &mut RgbaImage) {
+    let (w, h) = cell.dimensions();
+    let (half_w, half_h) = (w / 2, h / 2);
+    if half_w == 0 || half_h == 0 {
+        return;
+    }
+    let mix = |a: Rgba<u8>, b: Rgba<u8>| Rgba([0, 1, 2, 3].map(|i| ((a[i] as u16 * 3 + b[i] as u16 + 2) / 4) as u8));
+    for y in 0..h {
+        let (l, r) = (*cell.get_pixel(half_w - 1, y), *cell.get_pixel(half_w, y));
+        cell.put_pixel(half_w - 1, y, mix(l, r));
+        cell.put_pixel(half_w, y, mix(r, l));
+    }
+    for x in 0..w {
+        let (u, d) = (*cell.get_pixel(x, half_h - 1), *cell.get_pixel(x, half_h));
+        cell.put_pixel(x, half_h - 1, mix(u, d));
+        cell.put_pixel(x, half_h, mix(d, u));
     }
-    Ok(out)
 }
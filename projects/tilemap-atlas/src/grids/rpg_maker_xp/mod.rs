@@ -59,7 +59,7 @@ impl GridCornerRMXP {
     where
         P: AsRef<Path>,
     {
-        let image = image::open(path)?.to_rgba8();
+        let image = open_image(path)?;
         let (w, h) = image.dimensions();
         if w % 6 != 0 || h % 8 != 0 {
             io_error(
@@ -85,3 +85,10 @@ impl GridCornerRMXP {
         save_as_png(&self.image, path)
     }
 }
+
+/// Borrow the source image of the tile set.
+impl AsRef<RgbaImage> for GridCornerRMXP {
+    fn as_ref(&self) -> &RgbaImage {
+        &self.image
+    }
+}
@@ -1,5 +1,9 @@
 use super::*;
-use crate::{GridCompleteAtlas, GridCornerRMVX};
+use crate::{
+    grids::rpg_maker_vx::{dim_unused_cells, rpg4x6_wang_quadrants},
+    traits::dimension_error,
+    GridCompleteAtlas, GridCornerAtlas, GridCornerRMVX,
+};
 
 impl GridCornerRMXP {
     /// Returns a new `GridCompleteAtlas` from the current `GridCornerRMVX`.
@@ -19,7 +23,10 @@ impl GridCornerRMXP {
         let mut output = RgbaImage::new(w * 2, h * 3);
         for i in 0..2 {
             for j in 0..3 {
-                let (x, y) = rpg6x8_to_rpg4x6(i, j);
+                let Ok((x, y)) = rpg6x8_to_rpg4x6(i, j)
+                else {
+                    continue;
+                };
                 let view = self.image.view(x * w, y * h, w, h);
                 output.copy_from(&*view, i * w, j * h).ok();
             }
@@ -40,18 +47,68 @@ impl GridCornerRMXP {
     pub fn as_complete(&self) -> GridCompleteAtlas {
         self.as_rpg_maker_vx().as_complete()
     }
+    /// Returns a new standard form [`GridCornerAtlas`] from the current `GridCornerRMXP`.
+    ///
+    /// This conversion may causes loss some detail
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerRMXP;
+    /// let rpg = GridCornerRMXP::load("assets/rpg6x8.png").unwrap();
+    /// rpg.as_standard().unwrap().save("assets/rpg6x8-std.png").unwrap();
+    /// ```
+    pub fn as_standard(&self) -> ImageResult<GridCornerAtlas> {
+        self.as_rpg_maker_vx().as_standard()
+    }
+    /// Returns a new standard form [`GridCornerAtlas`], and cross-fade the 1px borders where the quadrants meet.
+    ///
+    /// See [`GridCornerRMVX::as_standard_cross_faded`] for details.
+    pub fn as_standard_cross_faded(&self) -> ImageResult<GridCornerAtlas> {
+        self.as_rpg_maker_vx().as_standard_cross_faded()
+    }
+    /// Find the cells of the sheet used by [`GridCornerRMXP::as_standard`], indexed by `[row][column]`.
+    ///
+    /// The conversion only reads 6 of the 12 blocks, the remaining cells are free for other art.
+    pub fn coverage(&self) -> [[bool; 6]; 8] {
+        let mut used = [[false; 6]; 8];
+        for mask in 0..16 {
+            for (x, y) in rpg4x6_wang_quadrants(mask).into_iter().flatten() {
+                let Ok((bx, by)) = rpg6x8_to_rpg4x6(x / 2, y / 2)
+                else {
+                    continue;
+                };
+                used[(by * 2 + y % 2) as usize][(bx * 2 + x % 2) as usize] = true;
+            }
+        }
+        used
+    }
+    /// Dim the cells that [`GridCornerRMXP::coverage`] reports as unused, so artists can see what is safe to repurpose.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use tileset::GridCornerRMXP;
+    /// let rpg = GridCornerRMXP::load("assets/rpg6x8.png").unwrap();
+    /// rpg.coverage_image().save("assets/rpg6x8-coverage.png").unwrap();
+    /// ```
+    pub fn coverage_image(&self) -> RgbaImage {
+        let used = self.coverage();
+        dim_unused_cells(&self.image, self.cell_w, self.cell_h, |x, y| used[y as usize][x as usize])
+    }
 }
 
-fn rpg6x8_to_rpg4x6(x: u32, y: u32) -> (u32, u32) {
-    match (x, y) {
+fn rpg6x8_to_rpg4x6(x: u32, y: u32) -> ImageResult<(u32, u32)> {
+    let block = match (x, y) {
         (0, 0) => (1, 0),
         (0, 1) => (0, 1),
         (0, 2) => (0, 3),
         (1, 0) => (2, 0),
         (1, 1) => (2, 1),
         (1, 2) => (2, 3),
-        _ => unreachable!(),
-    }
+        _ => dimension_error()?,
+    };
+    Ok(block)
 }
 
 /// ```js
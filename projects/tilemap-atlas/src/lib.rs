@@ -9,13 +9,29 @@ pub use image::{RgbaImage, SubImage};
 mod animations;
 mod file_system;
 mod grids;
+pub mod mask;
+mod render;
 pub mod utils;
+#[cfg(feature = "ktx2")]
+pub use crate::grids::corner_set::CompressionFormat;
+#[cfg(feature = "stream")]
+pub use crate::render::render_chunks_stream;
 pub use crate::{
-    animations::standard::AnimationFrame,
+    animations::standard::{AnimationCursor, AnimationDelta, AnimationFrame},
     file_system::{FileSystemTiles, TileAtlasData},
     grids::{
-        bg_set::GridSimpleAtlas, complete::GridCompleteAtlas, corner_set::GridCornerAtlas, corner_wang::GridCornerWang,
-        edge_set::GridEdgeAtlas, edge_wang::GridEdgeWang, rpg_maker_vx::GridCornerRMVX, rpg_maker_xp::GridCornerRMXP,
+        bg_set::GridSimpleAtlas,
+        complete::GridCompleteAtlas,
+        corner_set::{
+            CornerFrame, CornerManifest, CornerManifestMask, GridCornerAtlas, GridCornerAtlasBuilder, TrimmedAtlas,
+            TrimmedFrame,
+        },
+        corner_wang::GridCornerWang,
+        edge_set::GridEdgeAtlas,
+        edge_wang::GridEdgeWang,
+        rpg_maker_vx::GridCornerRMVX,
+        rpg_maker_xp::GridCornerRMXP,
     },
+    render::{render_map, render_map_at, AnimationPhase, ChunkId, TilemapRenderer},
     traits::{GridAtlas, TilesProvider},
 };
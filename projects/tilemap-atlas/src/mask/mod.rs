@@ -0,0 +1,37 @@
+//! Named corner masks, as produced by [`grid_corner_mask`](crate::utils::grid_corner_mask).
+//!
+//! Bit 0 is the upper left corner, bit 1 the upper right, bit 2 the lower left and bit 3 the lower right, a set bit
+//! means the terrain covers that corner.
+
+/// No corner is covered, the tile stands alone
+pub const ISOLATED: u8 = 0b0000;
+/// Only the upper left corner is covered
+pub const UPPER_LEFT: u8 = 0b0001;
+/// Only the upper right corner is covered
+pub const UPPER_RIGHT: u8 = 0b0010;
+/// Both upper corners are covered, an edge along the top
+pub const UPPER: u8 = 0b0011;
+/// Only the lower left corner is covered
+pub const LOWER_LEFT: u8 = 0b0100;
+/// Both left corners are covered, an edge along the left
+pub const LEFT: u8 = 0b0101;
+/// The upper right and lower left corners are covered
+pub const ANTI_DIAGONAL: u8 = 0b0110;
+/// All corners except the lower right are covered, an inner corner
+pub const NOT_LOWER_RIGHT: u8 = 0b0111;
+/// Only the lower right corner is covered
+pub const LOWER_RIGHT: u8 = 0b1000;
+/// The upper left and lower right corners are covered
+pub const DIAGONAL: u8 = 0b1001;
+/// Both right corners are covered, an edge along the right
+pub const RIGHT: u8 = 0b1010;
+/// All corners except the lower left are covered, an inner corner
+pub const NOT_LOWER_LEFT: u8 = 0b1011;
+/// Both lower corners are covered, an edge along the bottom
+pub const LOWER: u8 = 0b1100;
+/// All corners except the upper right are covered, an inner corner
+pub const NOT_UPPER_RIGHT: u8 = 0b1101;
+/// All corners except the upper left are covered, an inner corner
+pub const NOT_UPPER_LEFT: u8 = 0b1110;
+/// All corners are covered, the tile is surrounded by the terrain
+pub const SURROUNDED: u8 = 0b1111;
@@ -0,0 +1,154 @@
+use super::*;
+use crate::traits::io_error;
+use image::ImageResult;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    io::ErrorKind,
+};
+
+/// The position of a chunk in a [`TilemapRenderer`], counted in chunks from the top left corner
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ChunkId {
+    /// The column of the chunk
+    pub x: u32,
+    /// The row of the chunk
+    pub y: u32,
+}
+
+/// Chunks are ordered row by row
+impl Ord for ChunkId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for ChunkId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A terrain grid rendered in chunks, only the chunks touched by an edit are rendered again.
+///
+/// The grid has the same layout as [`render_map`], every tile reads its corners from the four vertices around it, so
+/// toggling a vertex on a chunk border changes tiles in up to four chunks.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tileset::{GridCornerAtlas, TilemapRenderer};
+/// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+/// let mut map = TilemapRenderer::new(atlas, 65, 65, 16).unwrap();
+/// map.set_tile(10, 20, true);
+/// for (id, image) in map.render_dirty() {
+///     image.save(format!("assets/chunk-{}-{}.png", id.x, id.y)).unwrap();
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TilemapRenderer {
+    atlas: GridCornerAtlas,
+    grid: Vec<Vec<bool>>,
+    chunk_size: u32,
+    chunks: BTreeMap<ChunkId, RgbaImage>,
+    dirty: BTreeSet<ChunkId>,
+}
+
+impl TilemapRenderer {
+    /// Create an empty grid of `width * height` vertices, split into chunks of `chunk_size * chunk_size` tiles.
+    ///
+    /// Every chunk starts dirty, so the first [`TilemapRenderer::render_dirty`] renders the whole map.
+    pub fn new(atlas: GridCornerAtlas, width: u32, height: u32, chunk_size: u32) -> ImageResult<Self> {
+        if chunk_size == 0 {
+            io_error("The chunk size must be greater than zero", ErrorKind::InvalidInput)?;
+        }
+        let grid = vec![vec![false; width as usize]; height as usize];
+        let mut out = Self { atlas, grid, chunk_size, chunks: BTreeMap::new(), dirty: BTreeSet::new() };
+        let (cols, rows) = out.get_chunk_count();
+        out.dirty = (0..rows).flat_map(|y| (0..cols).map(move |x| ChunkId { x, y })).collect();
+        Ok(out)
+    }
+    /// Get the number of tiles in each row and column, one less than the vertices.
+    pub fn get_tile_count(&self) -> (u32, u32) {
+        tile_count(&self.grid)
+    }
+    /// Get the number of chunks in each row and column.
+    pub fn get_chunk_count(&self) -> (u32, u32) {
+        let (cols, rows) = self.get_tile_count();
+        (cols.div_ceil(self.chunk_size), rows.div_ceil(self.chunk_size))
+    }
+    /// Check whether the terrain exists at the vertex, `false` if the vertex is out of the grid.
+    pub fn get_tile(&self, x: u32, y: u32) -> bool {
+        self.grid.get(y as usize).and_then(|r| r.get(x as usize)).copied().unwrap_or(false)
+    }
+    /// Set whether the terrain exists at the vertex, and mark the chunks whose tiles change mask as dirty.
+    ///
+    /// Setting a vertex to its current value marks nothing.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the vertex is out of the grid.
+    pub fn set_tile(&mut self, x: u32, y: u32, present: bool) {
+        let vertex = &mut self.grid[y as usize][x as usize];
+        if *vertex == present {
+            return;
+        }
+        *vertex = present;
+        let (cols, rows) = self.get_tile_count();
+        // the four tiles sharing the vertex as a corner
+        for ty in y.saturating_sub(1)..=y {
+            for tx in x.saturating_sub(1)..=x {
+                if tx < cols && ty < rows {
+                    self.dirty.insert(ChunkId { x: tx / self.chunk_size, y: ty / self.chunk_size });
+                }
+            }
+        }
+    }
+    /// Get the chunks that changed since the last [`TilemapRenderer::render_dirty`], in row-major order.
+    pub fn dirty_chunks(&self) -> Vec<ChunkId> {
+        self.dirty.iter().copied().collect()
+    }
+    /// Get the last rendered image of the chunk.
+    pub fn get_chunk(&self, id: ChunkId) -> Option<&RgbaImage> {
+        self.chunks.get(&id)
+    }
+    /// Render the dirty chunks again and return them in row-major order, the chunks are clean afterwards.
+    pub fn render_dirty(&mut self) -> Vec<(ChunkId, RgbaImage)> {
+        let dirty = std::mem::take(&mut self.dirty);
+        let mut out = Vec::with_capacity(dirty.len());
+        for id in dirty {
+            let image = self.render_chunk(id);
+            self.chunks.insert(id, image.clone());
+            out.push((id, image));
+        }
+        out
+    }
+    fn render_chunk(&self, id: ChunkId) -> RgbaImage {
+        render_chunk(&self.atlas, &self.grid, self.chunk_size, id)
+    }
+}
+
+/// Get the number of tiles in each row and column of a vertex grid, the shortest row limits the columns like
+/// [`render_map`].
+pub(super) fn tile_count<R>(grid: &[R]) -> (u32, u32)
+where
+    R: AsRef<[bool]>,
+{
+    let rows = grid.len().saturating_sub(1) as u32;
+    let cols = grid.iter().map(|r| r.as_ref().len()).min().unwrap_or(0).saturating_sub(1) as u32;
+    (cols, rows)
+}
+
+/// Render the tiles of one chunk, reading the vertices on its border as well.
+pub(super) fn render_chunk<R>(atlas: &GridCornerAtlas, grid: &[R], chunk_size: u32, id: ChunkId) -> RgbaImage
+where
+    R: AsRef<[bool]>,
+{
+    let (cols, rows) = tile_count(grid);
+    let x0 = (id.x * chunk_size) as usize;
+    let y0 = (id.y * chunk_size) as usize;
+    let x1 = (x0 + chunk_size as usize).min(cols as usize);
+    let y1 = (y0 + chunk_size as usize).min(rows as usize);
+    let grid: Vec<&[bool]> = grid[y0..=y1].iter().map(|r| &r.as_ref()[x0..=x1]).collect();
+    render_region(atlas, &grid, (x0, y0), Duration::ZERO, AnimationPhase::Lockstep)
+}
@@ -0,0 +1,128 @@
+use crate::{utils::grid_corner_mask, GridCornerAtlas};
+use image::{imageops::replace, RgbaImage};
+use std::time::Duration;
+
+mod chunked;
+#[cfg(feature = "stream")]
+mod stream;
+
+pub use self::chunked::{ChunkId, TilemapRenderer};
+#[cfg(feature = "stream")]
+pub use self::stream::render_chunks_stream;
+
+/// How the animation frames of different cells relate to each other
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum AnimationPhase {
+    /// All cells show the same frame at the same time
+    #[default]
+    Lockstep,
+    /// Each cell starts at a different frame derived from its position, so large areas do not pulse in unison
+    PerCell,
+    /// Each cell starts at a frame picked by a blue noise pattern, so neighboring cells rarely show the same frame
+    BlueNoise,
+}
+
+/// Render a terrain grid with a corner atlas.
+///
+/// The grid describes whether the terrain exists at each vertex, every tile reads its four corners from the vertices
+/// around it, so a `w * h` grid renders `(w - 1) * (h - 1)` tiles.
+///
+/// If the atlas is not animated, each tile picks one of the variants of its mask by a blue noise pattern, so large
+/// areas of the same mask do not repeat a single cell.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tileset::{render_map, GridCornerAtlas};
+/// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+/// let grid = vec![vec![true, false], vec![true, true]];
+/// render_map(&atlas, &grid).save("assets/map.png").unwrap();
+/// ```
+pub fn render_map<R>(atlas: &GridCornerAtlas, grid: &[R]) -> RgbaImage
+where
+    R: AsRef<[bool]>,
+{
+    render_map_at(atlas, grid, Duration::ZERO, AnimationPhase::Lockstep)
+}
+
+/// Render a terrain grid with a corner atlas at the given time point.
+///
+/// Masks with more than one variant are animated with the frame duration of the atlas, the phase only decides which
+/// frame each tile starts at. Atlases without a frame duration pick static variants as [`render_map`] does, whatever
+/// the phase. Missing cells follow the fallback policy of [`GridCornerAtlas::select_variant`].
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use tileset::{render_map_at, AnimationPhase, GridCornerAtlas};
+/// let atlas = GridCornerAtlas::load("assets/water-std.png").unwrap();
+/// let grid = vec![vec![true, false], vec![true, true]];
+/// let frame = render_map_at(&atlas, &grid, Duration::from_millis(500), AnimationPhase::PerCell);
+/// ```
+pub fn render_map_at<R>(atlas: &GridCornerAtlas, grid: &[R], time: Duration, phase: AnimationPhase) -> RgbaImage
+where
+    R: AsRef<[bool]>,
+{
+    render_region(atlas, grid, (0, 0), time, phase)
+}
+
+/// Render a part of a larger grid, the origin is the position of the first tile in the whole map so the variants
+/// and phases match the ones of the whole map.
+pub(crate) fn render_region<R>(
+    atlas: &GridCornerAtlas,
+    grid: &[R],
+    origin: (usize, usize),
+    time: Duration,
+    phase: AnimationPhase,
+) -> RgbaImage
+where
+    R: AsRef<[bool]>,
+{
+    let (cell_w, cell_h) = atlas.get_cell_size();
+    let rows = grid.len().saturating_sub(1);
+    let cols = grid.iter().map(|r| r.as_ref().len()).min().unwrap_or(0).saturating_sub(1);
+    let step = match atlas.frame_ms {
+        0 => 0,
+        ms => (time.as_millis() / ms as u128) as usize,
+    };
+    let mut output = RgbaImage::new(cols as u32 * cell_w, rows as u32 * cell_h);
+    for y in 0..rows {
+        let (upper, lower) = (grid[y].as_ref(), grid[y + 1].as_ref());
+        for x in 0..cols {
+            let mask = grid_corner_mask(upper[x], upper[x + 1], lower[x], lower[x + 1]);
+            let (mx, my) = (origin.0 + x, origin.1 + y);
+            let frames = atlas.get_count(mask).max(1) as usize;
+            let index = match atlas.frame_ms {
+                // static variants
+                0 => (blue_noise(mx, my) * frames as f64) as usize,
+                _ => {
+                    let offset = match phase {
+                        AnimationPhase::Lockstep => 0,
+                        AnimationPhase::PerCell => mx.wrapping_mul(7).wrapping_add(my.wrapping_mul(13)),
+                        AnimationPhase::BlueNoise => (blue_noise(mx, my) * frames as f64) as usize,
+                    };
+                    step.wrapping_add(offset)
+                }
+            };
+            // tiles without any cell stay transparent, see the fallback policy of `select_variant`
+            let Some(cell) = atlas.select_variant(mask, (index % frames) as u32)
+            else {
+                continue;
+            };
+            replace(&mut output, cell, (x as u32 * cell_w) as i64, (y as u32 * cell_h) as i64);
+        }
+    }
+    output
+}
+
+/// Sample the R2 low discrepancy pattern at the cell, in range `[0, 1)`.
+///
+/// Neighboring cells are spread far apart, which approximates blue noise without a precomputed texture, see
+/// <https://extremelearning.com.au/unreasonable-effectiveness-of-quasirandom-sequences/>.
+fn blue_noise(x: usize, y: usize) -> f64 {
+    // 1 / g and 1 / g^2 where g is the plastic number
+    const A1: f64 = 0.7548776662466927;
+    const A2: f64 = 0.5698402909980532;
+    (x as f64 * A1 + y as f64 * A2).fract()
+}
@@ -0,0 +1,53 @@
+use super::{
+    chunked::{render_chunk, tile_count},
+    *,
+};
+use futures_util::{stream, Stream};
+
+/// Render a terrain grid chunk by chunk as a stream, so a client can show the map progressively instead of waiting for
+/// the whole image.
+///
+/// Each chunk is rendered only when the stream is polled, starting from the chunk at the center of the map and moving
+/// outwards, chunks at the same distance come in row-major order. The chunks are the same as the ones
+/// [`TilemapRenderer`] renders for the grid.
+///
+/// A chunk size of zero yields no chunk, and like [`render_map`] the shortest row limits the columns of a jagged grid.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use futures_util::StreamExt;
+/// # use tileset::{render_chunks_stream, GridCornerAtlas};
+/// # async fn run() {
+/// let atlas = GridCornerAtlas::load("assets/grass-std.png").unwrap();
+/// let grid = vec![vec![true; 65]; 65];
+/// let mut chunks = std::pin::pin!(render_chunks_stream(&atlas, &grid, 16));
+/// while let Some((id, image)) = chunks.next().await {
+///     image.save(format!("assets/chunk-{}-{}.png", id.x, id.y)).unwrap();
+/// }
+/// # }
+/// ```
+pub fn render_chunks_stream<'a, R>(
+    atlas: &'a GridCornerAtlas,
+    grid: &'a [R],
+    chunk_size: u32,
+) -> impl Stream<Item = (ChunkId, RgbaImage)> + 'a
+where
+    R: AsRef<[bool]>,
+{
+    let (cols, rows) = match chunk_size {
+        0 => (0, 0),
+        _ => {
+            let (cols, rows) = tile_count(grid);
+            (cols.div_ceil(chunk_size), rows.div_ceil(chunk_size))
+        }
+    };
+    let mut order: Vec<ChunkId> = (0..rows).flat_map(|y| (0..cols).map(move |x| ChunkId { x, y })).collect();
+    // distance between the chunk center and the map center, doubled to stay on integers
+    order.sort_by_key(|id| {
+        let dx = (2 * id.x + 1) as i64 - cols as i64;
+        let dy = (2 * id.y + 1) as i64 - rows as i64;
+        (dx * dx + dy * dy, *id)
+    });
+    stream::iter(order.into_iter().map(move |id| (id, render_chunk(atlas, grid, chunk_size, id))))
+}
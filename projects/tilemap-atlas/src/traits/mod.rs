@@ -1,8 +1,12 @@
 use image::{
-    error::{LimitError, LimitErrorKind},
+    error::{DecodingError, ImageFormatHint, LimitError, LimitErrorKind, UnsupportedError, UnsupportedErrorKind},
     ImageError, ImageResult, RgbaImage, SubImage,
 };
-use std::io::{Error, ErrorKind};
+use std::{
+    fmt::{Display, Formatter},
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
 
 use rand_core::RngCore;
 
@@ -32,3 +36,49 @@ where
 {
     Err(ImageError::IoError(Error::new(kind, message.to_string())))
 }
+
+/// An io error that happened on a file
+#[derive(Debug)]
+struct PathError {
+    path: PathBuf,
+    source: Error,
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Failed to open {}: {}", self.path.display(), self.source)
+    }
+}
+
+impl std::error::Error for PathError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Open an image as rgba, the error message contains the path of the file that failed.
+///
+/// The variant of the error is kept, decoding and unsupported errors carry the path as their format hint and wrap the
+/// original error as the source, io errors keep their kind and wrap the original error as the source.
+pub(crate) fn open_image<P>(path: P) -> ImageResult<RgbaImage>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let hint = || ImageFormatHint::Name(path.display().to_string());
+    match image::open(path) {
+        Ok(o) => Ok(o.to_rgba8()),
+        Err(ImageError::IoError(e)) => {
+            Err(ImageError::IoError(Error::new(e.kind(), PathError { path: path.to_path_buf(), source: e })))
+        }
+        Err(ImageError::Decoding(e)) => Err(ImageError::Decoding(DecodingError::new(hint(), e))),
+        Err(ImageError::Unsupported(e)) => {
+            let kind = match e.kind() {
+                UnsupportedErrorKind::Format(_) => UnsupportedErrorKind::Format(hint()),
+                kind => kind,
+            };
+            Err(ImageError::Unsupported(UnsupportedError::from_format_and_kind(hint(), kind)))
+        }
+        Err(e) => Err(e),
+    }
+}
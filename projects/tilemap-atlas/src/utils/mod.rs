@@ -1,8 +1,13 @@
-use crate::{grids::rpg_maker_xp::GridCornerRMXP, GridCompleteAtlas, GridCornerRMVX};
+use crate::{
+    grids::rpg_maker_xp::GridCornerRMXP,
+    traits::{io_error, open_image},
+    GridCompleteAtlas, GridCornerAtlas, GridCornerRMVX, GridEdgeWang,
+};
 use image::{ColorType, GenericImageView, ImageFormat, ImageResult, RgbaImage};
 use std::{
     collections::BTreeMap,
     fmt::{Display, Formatter},
+    io::ErrorKind,
     path::{Path, PathBuf},
 };
 
@@ -13,7 +18,7 @@ where
     let path = path.as_ref().canonicalize()?;
     let dir = path.parent().expect("The path must have a parent directory");
     let name = path.file_stem().expect("The path must have a file name");
-    let image = image::open(&path)?;
+    let image = open_image(&path)?;
     let (width, height) = image.dimensions();
     let cell_width = width / cols;
     let cell_height = height / rows;
@@ -198,7 +203,7 @@ where
 {
     let path = image.as_ref().canonicalize()?;
     let new_name = path.file_stem().and_then(|s| s.to_str()).map(|s| format!("{}-std.png", s)).unwrap();
-    let raw = image::open(image.as_ref())?.to_rgba8();
+    let raw = open_image(image.as_ref())?;
     let new = GridCompleteAtlas::from_blob7x7a(&raw, raw.width() / 7, raw.height() / 7);
     new.save(path.with_file_name(new_name))
 }
@@ -233,12 +238,126 @@ where
     rpg.as_complete().save(output)
 }
 
+/// The layout of a tile sheet on disk
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SheetFormat {
+    /// 6x8 corner tile set used by RPG Maker XP
+    RpgMakerXp,
+    /// 4x6 corner tile set used by RPG Maker VX, MV and MZ
+    RpgMakerVx,
+    /// 4x4 wang corner tile set
+    WangCorner,
+    /// 4x4 wang edge tile set
+    WangEdge,
+    /// 7x7 blob tile set type A
+    Blob7x7A,
+    /// 12x4 complete set
+    Complete,
+    /// The standard form, a row of 16 corner cells with variants placed directly below
+    Standard,
+}
+
+/// Convert a tile sheet from one format to another, the output is always saved as png.
+///
+/// Corner formats can be converted to [`SheetFormat::Standard`] and [`SheetFormat::WangCorner`], blob and edge formats
+/// can be converted to [`SheetFormat::Complete`], other combinations return an unsupported error.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tileset::utils::{convert, SheetFormat};
+/// convert(
+///     "assets/grass-xp.png",
+///     SheetFormat::RpgMakerXp,
+///     "assets/grass-std.png",
+///     SheetFormat::Standard,
+/// )
+/// .unwrap();
+/// ```
+pub fn convert<I, O>(input: I, input_format: SheetFormat, output: O, output_format: SheetFormat) -> ImageResult<()>
+where
+    I: AsRef<Path>,
+    O: AsRef<Path>,
+{
+    let raw = open_image(input)?;
+    let (w, h) = raw.dimensions();
+    match output_format {
+        SheetFormat::Complete => {
+            let complete = match input_format {
+                SheetFormat::RpgMakerXp => GridCornerRMXP::new(&raw, (0, 0), (w / 6, h / 8))?.as_complete(),
+                SheetFormat::RpgMakerVx => GridCornerRMVX::new(&raw, (0, 0), (w / 4, h / 6))?.as_complete(),
+                SheetFormat::WangEdge => GridCompleteAtlas::new(GridCompleteAtlas::from_edge4x4(&raw, w / 4, h / 4))?,
+                SheetFormat::Blob7x7A => GridCompleteAtlas::new(GridCompleteAtlas::from_blob7x7a(&raw, w / 7, h / 7))?,
+                SheetFormat::Complete => GridCompleteAtlas::new(raw)?,
+                _ => return unsupported_conversion(input_format, output_format),
+            };
+            complete.save(output)
+        }
+        SheetFormat::Standard | SheetFormat::WangCorner => {
+            let atlas = match input_format {
+                SheetFormat::RpgMakerXp => GridCornerRMXP::new(&raw, (0, 0), (w / 6, h / 8))?.as_standard()?,
+                SheetFormat::RpgMakerVx => GridCornerRMVX::new(&raw, (0, 0), (w / 4, h / 6))?.as_standard()?,
+                SheetFormat::WangCorner => GridCornerAtlas::from_wang(&raw)?,
+                SheetFormat::WangEdge => GridEdgeWang::new("", w / 4, h / 4).as_standard("", &raw)?.0,
+                SheetFormat::Standard => GridCornerAtlas::from_standard(&raw)?,
+                _ => return unsupported_conversion(input_format, output_format),
+            };
+            match output_format {
+                SheetFormat::WangCorner => save_as_png(&atlas.to_wang(), output),
+                _ => atlas.save(output),
+            }
+        }
+        _ => unsupported_conversion(input_format, output_format),
+    }
+}
+
+/// Compare the same art set drawn as a RPG Maker XP sheet and a 4x4 wang corner sheet, for checking that both import
+/// paths agree.
+///
+/// Both sheets are converted to standard form, then the first cells of each mask are compared. Returns the mean
+/// absolute difference of all channels for every mask, in range `[0, 1]`. Consistent art reports `0.0`, hand drawn
+/// sheets usually stay below `0.02`, anything above `0.1` points to a misplaced or mismatched cell.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use tileset::utils::compare_dialects;
+/// let xp = image::open("assets/grass-xp.png").unwrap().to_rgba8();
+/// let wang = image::open("assets/grass-wang.png").unwrap().to_rgba8();
+/// for (mask, difference) in compare_dialects(&xp, &wang).unwrap() {
+///     assert!(difference < 0.02, "mask 0b{:04b} differs by {}", mask, difference);
+/// }
+/// ```
+pub fn compare_dialects(rmxp_sheet: &RgbaImage, wang_sheet: &RgbaImage) -> ImageResult<Vec<(u8, f64)>> {
+    let (w, h) = rmxp_sheet.dimensions();
+    let xp = GridCornerRMXP::new(rmxp_sheet, (0, 0), (w / 6, h / 8))?.as_standard()?;
+    let wang = GridCornerAtlas::from_wang(wang_sheet)?;
+    if xp.get_cell_size() != wang.get_cell_size() {
+        io_error(
+            format!("The cell size {:?} of the rpg maker sheet does not match {:?}", xp.get_cell_size(), wang.get_cell_size()),
+            ErrorKind::InvalidInput,
+        )?
+    }
+    let difference = (0..16u8)
+        .map(|mask| {
+            let (lhs, rhs) = (xp.get_corner_mask(mask).as_raw(), wang.get_corner_mask(mask).as_raw());
+            let sum: u64 = lhs.iter().zip(rhs.iter()).map(|(a, b)| a.abs_diff(*b) as u64).sum();
+            (mask, sum as f64 / (lhs.len() as f64 * 255.0))
+        })
+        .collect();
+    Ok(difference)
+}
+
+fn unsupported_conversion(input: SheetFormat, output: SheetFormat) -> ImageResult<()> {
+    io_error(format!("Conversion from {:?} to {:?} is not supported", input, output), ErrorKind::Unsupported)
+}
+
 fn image_with_new_path<P>(image: P) -> ImageResult<(RgbaImage, PathBuf)>
 where
     P: AsRef<Path>,
 {
     let path = image.as_ref().canonicalize()?;
-    let raw = image::open(image.as_ref())?.to_rgba8();
+    let raw = open_image(image.as_ref())?;
     let new_name = path.file_stem().and_then(|s| s.to_str()).map(|s| format!("{}-std.png", s)).unwrap();
     let new_path = path.with_file_name(new_name);
     Ok((raw, new_path))
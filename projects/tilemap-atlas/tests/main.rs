@@ -1,9 +1,47 @@
-use std::path::Path;
+use image::{
+    imageops::{overlay, rotate90},
+    GenericImageView, ImageError, Rgba, RgbaImage,
+};
+use std::{
+    cmp::Ordering,
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use tileset::{
-    utils::{convert_blob7x7a, convert_edge4x4, convert_rpg4x6, convert_rpg6x8, MaskBuilder},
-    FileSystemTiles, GridCornerWang, TileAtlasData,
+    mask, render_map, render_map_at,
+    utils::{
+        compare_dialects, convert, convert_blob7x7a, convert_edge4x4, convert_rpg4x6, convert_rpg6x8, grid_corner_mask,
+        MaskBuilder, SheetFormat,
+    },
+    AnimationDelta, AnimationFrame, AnimationPhase, ChunkId, CornerFrame, FileSystemTiles, GridCornerAtlas, GridCornerRMVX,
+    GridCornerRMXP, GridCornerWang, GridSimpleAtlas, TileAtlasData, TilemapRenderer,
 };
 
+/// The path of a fixture in the tests folder.
+fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join(path)
+}
+
+/// The 4x4 wang sheet of `tests/atlas3`, 32px cells.
+fn wang_sheet() -> RgbaImage {
+    image::open(fixture("atlas3/atlas.png")).unwrap().to_rgba8()
+}
+
+/// The corner atlas read from [`wang_sheet`].
+fn wang_atlas() -> GridCornerAtlas {
+    GridCornerAtlas::from_wang(&wang_sheet()).unwrap()
+}
+
+/// An empty directory for the test, the process id keeps concurrent `cargo test` runs apart.
+fn temp_dir(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("tileset-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
 #[test]
 fn ready() {
     println!("it works!")
@@ -77,3 +115,1245 @@ fn test22() {
 
     println!("{}", MaskBuilder::blob7x7_set());
 }
+
+#[test]
+fn test_corner_count() {
+    let json = r#"{"key": "a", "cell_w": 32, "cell_h": 32, "count": [1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]}"#;
+    assert!(serde_json::from_str::<GridCornerAtlas>(json).is_ok());
+    let json = r#"{"key": "a", "cell_w": 32, "cell_h": 32, "count": [1, 1, 1, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1]}"#;
+    let error = serde_json::from_str::<GridCornerAtlas>(json).unwrap_err();
+    assert!(error.to_string().contains("0b0011"));
+}
+
+#[test]
+fn test_corner_layers() {
+    let atlas = wang_atlas();
+    let layers = atlas.to_layers();
+    assert_eq!(layers.len(), 16);
+    for (mask, layer) in layers.iter().enumerate() {
+        assert_eq!(layer.dimensions(), atlas.get_cell_size());
+        assert_eq!(layer, atlas.get_corner_mask(mask as u8));
+    }
+}
+
+#[test]
+fn test_corner_order() {
+    let atlas = wang_atlas();
+    let mut faded = atlas.clone();
+    faded.set_mask_opacity(0b0011, 0.5).unwrap();
+    let set = BTreeSet::from([atlas.clone().with_key("b"), atlas.clone().with_key("a"), atlas.clone(), faded.clone()]);
+    assert_eq!(set.len(), 4);
+    assert_eq!(set.iter().map(|a| a.get_key()).collect::<Vec<_>>(), ["", "", "a", "b"]);
+    assert_ne!(atlas.cmp(&faded), Ordering::Equal);
+    assert_eq!(atlas.cmp(&atlas.clone()), Ordering::Equal);
+}
+
+#[test]
+fn test_texture_packer() {
+    let json = r#"{
+        "frames": {
+            "grass.png": {
+                "frame": {"x": 0, "y": 0, "w": 2, "h": 3},
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": {"x": 0, "y": 0, "w": 2, "h": 3},
+                "sourceSize": {"w": 2, "h": 3}
+            },
+            "water.png": {
+                "frame": {"x": 2, "y": 0, "w": 2, "h": 3},
+                "rotated": true,
+                "trimmed": false,
+                "spriteSourceSize": {"x": 0, "y": 0, "w": 2, "h": 3},
+                "sourceSize": {"w": 2, "h": 3}
+            }
+        },
+        "meta": {"image": "sheet.png", "size": {"w": 5, "h": 3}}
+    }"#;
+    let grass = RgbaImage::from_fn(2, 3, |x, y| Rgba([10 * x as u8, 10 * y as u8, 0, 255]));
+    let water = RgbaImage::from_fn(2, 3, |x, y| Rgba([0, 10 * x as u8, 10 * y as u8, 255]));
+    let mut sheet = RgbaImage::new(5, 3);
+    overlay(&mut sheet, &grass, 0, 0);
+    overlay(&mut sheet, &rotate90(&water), 2, 0);
+    let atlas = GridSimpleAtlas::from_texturepacker(json, &sheet).unwrap();
+    assert_eq!(atlas.get_names(), ["grass.png", "water.png"]);
+    assert_eq!(atlas.get_cell_size(), (2, 3));
+    assert_eq!(atlas.get_cell("grass.png"), Some(&grass));
+    assert_eq!(atlas.get_cell("water.png"), Some(&water));
+    assert_eq!(atlas.cmp(&atlas.clone()), Ordering::Equal);
+    assert!(BTreeSet::from([atlas.clone()]).contains(&atlas));
+}
+
+#[test]
+fn test_corner_opacity() {
+    let mut atlas = wang_atlas();
+    let before = atlas.get_corner_mask(0b0011).clone();
+    atlas.set_mask_opacity(0b0011, 0.5).unwrap();
+    assert_eq!(atlas.get_mask_opacity(0b0011), 128.0 / 255.0);
+    assert_eq!(atlas.get_mask_opacity(0b0001), 1.0);
+    for (old, new) in before.pixels().zip(atlas.get_corner_mask(0b0011).pixels()) {
+        assert_eq!(new[3], ((old[3] as u32 * 128 + 127) / 255) as u8);
+        assert_eq!(old.0[..3], new.0[..3]);
+    }
+    // the opacity is applied to the authored cells, so fading is never lossy
+    atlas.set_mask_opacity(0b0011, 0.0).unwrap();
+    atlas.set_mask_opacity(0b0011, 2.0).unwrap();
+    assert_eq!(atlas.get_mask_opacity(0b0011), 1.0);
+    assert_eq!(atlas.get_corner_mask(0b0011), &before);
+    atlas.set_mask_opacity(0b0011, 0.5).unwrap();
+    // scaling keeps the authored cells in step
+    let mut scaled = atlas.upscale_hq(2).unwrap();
+    scaled.set_mask_opacity(0b0011, 1.0).unwrap();
+    let authored = wang_atlas().upscale_hq(2).unwrap();
+    assert_eq!(scaled.get_corner_mask(0b0011), authored.get_corner_mask(0b0011));
+    assert!(atlas.set_mask_opacity(16, 0.5).is_err());
+    // the cells are not serialized
+    let mut bare: GridCornerAtlas = serde_json::from_str(&serde_json::to_string(&wang_atlas()).unwrap()).unwrap();
+    assert!(bare.set_mask_opacity(5, 0.5).is_err());
+    assert_eq!(bare.get_mask_opacity(5), 1.0);
+}
+
+#[test]
+fn test_corner_align() {
+    let mut raw = RgbaImage::from_pixel(129, 129, Rgba([255, 0, 0, 255]));
+    assert_eq!(GridCornerAtlas::try_align(&raw).unwrap(), (0, 0, 128, 128));
+    for i in 0..129 {
+        raw.put_pixel(0, i, Rgba([0, 0, 0, 0]));
+    }
+    assert_eq!(GridCornerAtlas::try_align(&raw).unwrap(), (1, 0, 128, 128));
+    assert!(GridCornerAtlas::try_align(&RgbaImage::new(3, 8)).is_err());
+}
+
+#[test]
+fn test_convert() {
+    let output = temp_dir("convert").join("wang-std.png");
+    convert(fixture("atlas3/atlas.png"), SheetFormat::WangCorner, &output, SheetFormat::Standard).unwrap();
+    let standard = GridCornerAtlas::load(&output).unwrap();
+    let raw = wang_sheet();
+    assert_eq!(standard.to_layers(), GridCornerAtlas::from_wang(&raw).unwrap().to_layers());
+    assert_eq!(standard.to_wang(), raw);
+    assert!(convert(fixture("atlas3/atlas.png"), SheetFormat::WangCorner, &output, SheetFormat::Complete).is_err());
+    assert!(GridCornerAtlas::from_standard(&RgbaImage::new(16, 0)).is_err());
+    assert!(GridCornerAtlas::from_standard(&RgbaImage::new(32, 3)).is_err());
+}
+
+#[test]
+fn test_render_animated() {
+    let mut sheet = RgbaImage::from_pixel(32, 4, Rgba([255, 0, 0, 255]));
+    for x in 0..32 {
+        for y in 2..4 {
+            sheet.put_pixel(x, y, Rgba([0, 0, 255, 255]));
+        }
+    }
+    let mut atlas = GridCornerAtlas::from_standard(&sheet).unwrap();
+    assert_eq!(atlas.get_count(0b1111), 2);
+    let grid = vec![vec![true, true, false], vec![true, true, true], vec![false, true, true]];
+    let still = render_map(&atlas, &grid);
+    assert_eq!(still.dimensions(), (4, 4));
+    assert_eq!(render_map_at(&atlas, &grid, Duration::from_millis(100), AnimationPhase::Lockstep), still);
+    atlas.set_frame_duration(Duration::from_millis(100));
+    let first = render_map_at(&atlas, &grid, Duration::from_millis(50), AnimationPhase::Lockstep);
+    let second = render_map_at(&atlas, &grid, Duration::from_millis(150), AnimationPhase::Lockstep);
+    // the first frame is the first variant everywhere, static atlases mix the variants instead
+    assert!(first.pixels().all(|p| p == &Rgba([255, 0, 0, 255])));
+    assert_ne!(first, still);
+    assert_ne!(first, second);
+    assert_eq!(second.get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+}
+
+#[test]
+#[cfg(feature = "http")]
+fn test_fs_http() {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+    };
+    let manifest = r#"{"target_size": [32, 32], "atlas": [["grass", {"type": "GridCornerWang", "key": "wang.png", "cell_w": 32, "cell_h": 32}]]}"#;
+    let wang = std::fs::read(fixture("atlas3/atlas.png")).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line).unwrap();
+            let (status, body) = match line.split_whitespace().nth(1) {
+                Some("/TileSet.json5") => ("200 OK", manifest.as_bytes()),
+                Some("/wang.png") => ("200 OK", wang.as_slice()),
+                _ => ("404 Not Found", &[][..]),
+            };
+            write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, body.len()).unwrap();
+            stream.write_all(body).unwrap();
+        }
+    });
+    let tiles = FileSystemTiles::open_url(&format!("http://{}/", address)).unwrap();
+    assert_eq!(tiles.get_target_size(), (32, 32));
+    let expected = wang_atlas();
+    assert_eq!(tiles.get_corner("grass", true, false, true, true, 0).as_ref(), Some(expected.get_corner_mask(0b1101)));
+    assert!(tiles.insert_atlas("other", TileAtlasData::GridCornerWang(Box::new(GridCornerWang::new("a", 32, 32)))).is_err());
+    assert!(FileSystemTiles::open_url(&format!("http://{}/missing", address)).is_err());
+}
+
+#[test]
+fn test_corner_wang_region() {
+    let first = wang_sheet();
+    let second = image::open(fixture("atlas4/atlas.png")).unwrap().to_rgba8();
+    let mut sheet = RgbaImage::new(256, 128);
+    overlay(&mut sheet, &first, 0, 0);
+    overlay(&mut sheet, &second, 128, 0);
+    let atlas = GridCornerAtlas::from_wang_region(&sheet, (128, 0), (32, 32)).unwrap();
+    assert_eq!(atlas.to_layers(), GridCornerAtlas::from_wang(&second).unwrap().to_layers());
+    assert!(GridCornerAtlas::from_wang_region(&sheet, (160, 0), (32, 32)).is_err());
+}
+
+#[test]
+fn test_corner_dedup() {
+    let mut sheet = RgbaImage::from_pixel(32, 2, Rgba([255, 0, 0, 255]));
+    for x in 0..4 {
+        for y in 0..2 {
+            sheet.put_pixel(x, y, Rgba([0, 255, 0, 255]));
+        }
+    }
+    let mut atlas = GridCornerAtlas::from_standard(&sheet).unwrap();
+    assert!(!Arc::ptr_eq(atlas.get_corner_shared(0b0010, 0), atlas.get_corner_shared(0b0011, 0)));
+    assert_eq!(atlas.dedup(), 14);
+    assert_eq!(atlas.dedup(), 0);
+    assert!(Arc::ptr_eq(atlas.get_corner_shared(0b0010, 0), atlas.get_corner_shared(0b0011, 0)));
+    assert!(!Arc::ptr_eq(atlas.get_corner_shared(0b0001, 0), atlas.get_corner_shared(0b0010, 0)));
+    atlas.set_mask_opacity(0b0011, 0.0).unwrap();
+    assert_eq!(atlas.get_corner_mask(0b0010).get_pixel(0, 0)[3], 255);
+    assert_eq!(atlas.get_corner_mask(0b0011).get_pixel(0, 0)[3], 0);
+}
+
+#[test]
+fn test_corner_strip() {
+    let atlas = wang_atlas();
+    let order = (0..16).collect::<Vec<u8>>();
+    let reversed = order.iter().rev().copied().collect::<Vec<u8>>();
+    let forward = atlas.to_strip(&order).unwrap();
+    let backward = atlas.to_strip(&reversed).unwrap();
+    assert_eq!(forward.dimensions(), (32 * 16, 32));
+    for i in 0..16 {
+        let a = forward.view(i * 32, 0, 32, 32).to_image();
+        let b = backward.view((15 - i) * 32, 0, 32, 32).to_image();
+        assert_eq!(a, b);
+    }
+    assert_eq!(atlas.to_strip(&[0b1111]).unwrap(), *atlas.get_corner_mask(0b1111));
+    assert!(atlas.to_strip(&[1, 2, 1]).is_err());
+    assert!(atlas.to_strip(&[16]).is_err());
+}
+
+#[test]
+fn test_decode_error_path() {
+    let root = temp_dir("decode-error");
+    let corrupt = root.join("corrupt-atlas.png");
+    std::fs::write(&corrupt, b"\x89PNG\r\n\x1a\nnot a png").unwrap();
+    let error = GridCornerAtlas::load(&corrupt).unwrap_err();
+    assert!(error.to_string().contains(&corrupt.display().to_string()), "{}", error);
+    // the variant is kept and the decoder error stays reachable as the source
+    assert!(matches!(error, ImageError::Decoding(_)), "{:?}", error);
+    assert!(std::error::Error::source(&error).is_some());
+    let error = GridCornerRMVX::load(&corrupt).unwrap_err();
+    assert!(error.to_string().contains(&corrupt.display().to_string()), "{}", error);
+    assert!(matches!(error, ImageError::Decoding(_)), "{:?}", error);
+    let missing = root.join("missing-atlas.png");
+    let error = convert(&missing, SheetFormat::WangCorner, &corrupt, SheetFormat::Standard).unwrap_err();
+    assert!(error.to_string().contains(&missing.display().to_string()), "{}", error);
+    assert!(matches!(&error, ImageError::IoError(e) if e.kind() == std::io::ErrorKind::NotFound), "{:?}", error);
+    let source = std::error::Error::source(&error).and_then(|e| e.downcast_ref::<std::io::Error>());
+    assert_eq!(source.map(|e| e.kind()), Some(std::io::ErrorKind::NotFound), "{:?}", error);
+}
+
+#[test]
+fn test_rpg_cross_fade() {
+    // every quarter of the 4x6 sheet has its own solid color
+    let sheet = RgbaImage::from_fn(8, 12, |x, y| Rgba([(x / 2 * 60) as u8, (y / 2 * 40) as u8, 0, 255]));
+    let rpg = GridCornerRMVX::new(&sheet, (0, 0), (2, 2)).unwrap();
+    let hard = rpg.as_standard().unwrap();
+    let soft = rpg.as_standard_cross_faded().unwrap();
+    let (hard, soft) = (hard.get_corner_mask(0b0011), soft.get_corner_mask(0b0011));
+    assert_eq!(hard.dimensions(), (4, 4));
+    // the seam between the upper and lower quadrants
+    for x in 0..4 {
+        let (u, d) = (hard.get_pixel(x, 1), hard.get_pixel(x, 2));
+        assert_ne!(u, d);
+        assert_ne!(soft.get_pixel(x, 1), u);
+        assert_ne!(soft.get_pixel(x, 2), d);
+        let delta = |a: &Rgba<u8>, b: &Rgba<u8>| (0..3).map(|i| (a[i] as i32 - b[i] as i32).abs()).sum::<i32>();
+        assert!(delta(soft.get_pixel(x, 1), soft.get_pixel(x, 2)) < delta(u, d));
+    }
+    // pixels away from the seams stay crisp
+    assert_eq!(soft.get_pixel(0, 0), hard.get_pixel(0, 0));
+    assert_eq!(soft.get_pixel(3, 3), hard.get_pixel(3, 3));
+}
+
+#[test]
+fn test_mask_constants() {
+    assert_eq!(mask::ISOLATED, grid_corner_mask(false, false, false, false));
+    assert_eq!(mask::UPPER_LEFT, grid_corner_mask(true, false, false, false));
+    assert_eq!(mask::UPPER_RIGHT, grid_corner_mask(false, true, false, false));
+    assert_eq!(mask::UPPER, grid_corner_mask(true, true, false, false));
+    assert_eq!(mask::LOWER_LEFT, grid_corner_mask(false, false, true, false));
+    assert_eq!(mask::LEFT, grid_corner_mask(true, false, true, false));
+    assert_eq!(mask::ANTI_DIAGONAL, grid_corner_mask(false, true, true, false));
+    assert_eq!(mask::NOT_LOWER_RIGHT, grid_corner_mask(true, true, true, false));
+    assert_eq!(mask::LOWER_RIGHT, grid_corner_mask(false, false, false, true));
+    assert_eq!(mask::DIAGONAL, grid_corner_mask(true, false, false, true));
+    assert_eq!(mask::RIGHT, grid_corner_mask(false, true, false, true));
+    assert_eq!(mask::NOT_LOWER_LEFT, grid_corner_mask(true, true, false, true));
+    assert_eq!(mask::LOWER, grid_corner_mask(false, false, true, true));
+    assert_eq!(mask::NOT_UPPER_RIGHT, grid_corner_mask(true, false, true, true));
+    assert_eq!(mask::NOT_UPPER_LEFT, grid_corner_mask(false, true, true, true));
+    assert_eq!(mask::SURROUNDED, grid_corner_mask(true, true, true, true));
+}
+
+#[test]
+fn test_animation_delta() {
+    let base = RgbaImage::from_fn(16, 16, |x, y| Rgba([x as u8 * 10, y as u8 * 10, 128, 255]));
+    let mut second = base.clone();
+    second.put_pixel(3, 4, Rgba([255, 255, 255, 255]));
+    second.put_pixel(5, 6, Rgba([0, 0, 0, 255]));
+    let mut third = second.clone();
+    third.put_pixel(10, 10, Rgba([1, 2, 3, 4]));
+    let frames = vec![base, second, third];
+    let animation = AnimationFrame::from_frames("water.png", frames.clone()).unwrap();
+    let delta = animation.compress_delta().unwrap();
+    assert_eq!(delta.get_frame_count(), 3);
+    assert_eq!(delta.stored_pixels(), 16 * 16 + 3 * 3 + 1);
+    assert_eq!(delta.decompress(), frames);
+    let json = serde_json::to_string(&delta).unwrap();
+    assert_eq!(serde_json::from_str::<AnimationDelta>(&json).unwrap().decompress(), frames);
+    assert!(serde_json::from_str::<AnimationDelta>(r#"{"first": [2, 2, [0, 0, 0]], "patches": []}"#).is_err());
+    // the frames are not serialized
+    let bare: AnimationFrame = serde_json::from_str(&serde_json::to_string(&animation).unwrap()).unwrap();
+    assert!(bare.compress_delta().is_none());
+    assert!(AnimationFrame::from_frames("bad.png", vec![RgbaImage::new(1, 1), RgbaImage::new(2, 2)]).is_err());
+}
+
+#[test]
+fn test_corner_mini() {
+    let colors = [[255, 0, 0, 255], [0, 255, 0, 255], [0, 0, 255, 255], [255, 255, 0, 255]];
+    let mini = RgbaImage::from_fn(8, 8, |x, y| Rgba(colors[(x / 4 + y / 4 * 2) as usize]));
+    let atlas = GridCornerAtlas::from_mini(&mini, 4, 4).unwrap();
+    assert_eq!(atlas.get_cell_size(), (4, 4));
+    assert_eq!(*atlas.get_corner_mask(mask::SURROUNDED), RgbaImage::from_pixel(4, 4, Rgba(colors[1])));
+    assert_eq!(*atlas.get_corner_mask(mask::ISOLATED), RgbaImage::new(4, 4));
+    let upper = atlas.get_corner_mask(mask::UPPER);
+    assert_eq!(upper.get_pixel(0, 0), &Rgba(colors[2]));
+    assert_eq!(upper.get_pixel(3, 1), &Rgba(colors[2]));
+    assert_eq!(upper.get_pixel(0, 3)[3], 0);
+    let corner = atlas.get_corner_mask(mask::LOWER_RIGHT);
+    assert_eq!(corner.get_pixel(3, 3), &Rgba(colors[0]));
+    assert_eq!(corner.get_pixel(0, 0)[3], 0);
+    let left = atlas.get_corner_mask(mask::LEFT);
+    assert_eq!(left.get_pixel(0, 3), &Rgba(colors[3]));
+    assert!(GridCornerAtlas::from_mini(&mini, 2, 4).is_err());
+}
+
+#[test]
+fn test_corner_tags() {
+    let mut atlas = GridCornerAtlas::default();
+    atlas.add_tag(mask::SURROUNDED, "slippery");
+    atlas.add_tag(mask::SURROUNDED, "damaging");
+    atlas.add_tag(mask::SURROUNDED, "slippery");
+    atlas.add_tag(mask::UPPER, "ledge");
+    let json = serde_json::to_string(&atlas).unwrap();
+    let atlas: GridCornerAtlas = serde_json::from_str(&json).unwrap();
+    assert_eq!(atlas.tags_for(mask::SURROUNDED), ["slippery", "damaging"]);
+    assert_eq!(atlas.tags_for(mask::UPPER), ["ledge"]);
+    assert!(atlas.tags_for(mask::ISOLATED).is_empty());
+}
+
+#[test]
+fn test_corner_try_from() {
+    let atlas = GridCornerAtlas::try_from(RgbaImage::new(128, 128)).unwrap();
+    assert_eq!(atlas.get_cell_size(), (32, 32));
+    assert!(GridCornerAtlas::try_from(RgbaImage::new(129, 128)).is_err());
+}
+
+#[test]
+fn test_corner_manifest_frames() {
+    let mut sheet = RgbaImage::new(32, 6);
+    for (x, y, pixel) in sheet.enumerate_pixels_mut() {
+        if y < 2 || x < 2 {
+            *pixel = Rgba([255, 0, 0, 255]);
+        }
+    }
+    let mut atlas = GridCornerAtlas::from_standard(&sheet).unwrap();
+    atlas.set_frame_duration(Duration::from_millis(120));
+    let manifest = atlas.to_manifest();
+    assert_eq!(manifest.masks.len(), 16);
+    assert_eq!(
+        manifest.masks[0].frames,
+        [
+            CornerFrame { rect: (0, 0, 2, 2), duration_ms: 120 },
+            CornerFrame { rect: (0, 2, 2, 2), duration_ms: 120 },
+            CornerFrame { rect: (0, 4, 2, 2), duration_ms: 120 },
+        ]
+    );
+    assert_eq!(manifest.masks[5].frames.len(), 1);
+    let json = serde_json::to_value(&manifest).unwrap();
+    assert_eq!(json["masks"][0]["frames"][2]["rect"], serde_json::json!([0, 4, 2, 2]));
+}
+
+#[test]
+fn test_corner_flip_y() {
+    let mut sheet = RgbaImage::new(32, 6);
+    for (x, y, pixel) in sheet.enumerate_pixels_mut() {
+        *pixel = Rgba([x as u8 * 8, y as u8 * 40, 0, 255]);
+    }
+    let atlas = GridCornerAtlas::from_standard(&sheet).unwrap();
+    let image = atlas.to_image_with(true);
+    let mut manifest = atlas.to_manifest_with(true);
+    assert!(manifest.flip_y);
+    assert_eq!(manifest.masks[0].frames[0].rect, (0, 4, 2, 2));
+    assert_eq!(manifest.masks[1].frames[0].rect, (2, 4, 2, 2));
+    for mask in 0..16u8 {
+        for (index, frame) in manifest.masks[mask as usize].frames.iter().enumerate() {
+            let (x, y, w, h) = frame.rect;
+            let cell = image::imageops::flip_vertical(&image.view(x, y, w, h).to_image());
+            assert_eq!(&cell, atlas.get_corner_variant(mask, index as u8));
+        }
+    }
+    assert_eq!(image::imageops::flip_vertical(&image), atlas.to_image_with(false));
+    manifest.flip_y();
+    assert_eq!(manifest, atlas.to_manifest());
+    manifest.flip_y();
+    manifest.flip_y();
+    assert_eq!(manifest, atlas.to_manifest());
+}
+
+#[test]
+fn test_fs_validate_insert() {
+    let root = temp_dir("validate-insert");
+    let tiles = FileSystemTiles::new(&root, 32, 32).unwrap();
+    let json = std::fs::read(root.join("TileSet.json5")).unwrap();
+    let odd = TileAtlasData::GridCornerWang(Box::new(GridCornerWang::new("a", 24, 24)));
+    assert!(tiles.validate_insert("odd", &odd).is_err());
+    assert!(tiles.insert_atlas("odd", odd).is_err());
+    assert!(tiles.get_atlas("odd", 0).is_none());
+    assert_eq!(std::fs::read(root.join("TileSet.json5")).unwrap(), json);
+    // the cells only need to scale to the target by an integer factor
+    for (w, h) in [(16, 16), (64, 64), (8, 8)] {
+        let data = TileAtlasData::GridCornerWang(Box::new(GridCornerWang::new("s", w, h)));
+        tiles.validate_insert("scaled", &data).unwrap();
+    }
+    let stretched = TileAtlasData::GridCornerWang(Box::new(GridCornerWang::new("s", 16, 32)));
+    assert!(tiles.validate_insert("stretched", &stretched).is_err());
+    let fine = TileAtlasData::GridCornerWang(Box::new(GridCornerWang::new("b", 32, 32)));
+    tiles.validate_insert("fine", &fine).unwrap();
+    tiles.insert_atlas("fine", fine.clone()).unwrap();
+    // inserting under an existing name replaces the atlas, so the dry run passes too
+    tiles.validate_insert("fine", &fine).unwrap();
+    let other = TileAtlasData::GridCornerWang(Box::new(GridCornerWang::new("c", 16, 16)));
+    tiles.insert_atlas("fine", other).unwrap();
+    assert_eq!(tiles.get_atlas("fine", 0).unwrap().get_name(), "c");
+}
+
+#[test]
+fn test_corner_upscale_hq() {
+    let atlas = wang_atlas();
+    for scale in 1..=4 {
+        let large = atlas.upscale_hq(scale).unwrap();
+        assert_eq!(large.get_cell_size(), (32 * scale, 32 * scale));
+        for mask in 0..16 {
+            assert_eq!(large.get_count(mask), atlas.get_count(mask));
+            assert_eq!(large.get_corner_mask(mask).dimensions(), (32 * scale, 32 * scale));
+        }
+    }
+    assert!(atlas.upscale_hq(5).is_err());
+    // a diagonal staircase is smoothed instead of doubled
+    let mut stairs = RgbaImage::new(32, 2);
+    stairs.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    let large = GridCornerAtlas::from_standard(&stairs).unwrap().upscale_hq(2).unwrap();
+    assert_eq!(large.get_corner_mask(0).get_pixel(1, 1), &Rgba([0, 0, 0, 0]));
+}
+
+#[test]
+fn test_corner_fallback() {
+    let mut atlas = wang_atlas();
+    let magenta = RgbaImage::from_pixel(32, 32, Rgba([255, 0, 255, 255]));
+    assert_eq!(atlas.get_corner_or(16, &magenta), &magenta);
+    assert_eq!(atlas.get_corner_or(255, &magenta), &magenta);
+    assert_eq!(atlas.get_corner_or(mask::SURROUNDED, &magenta), atlas.get_corner_mask(mask::SURROUNDED));
+    assert!(atlas.get_corner_or_fallback(16).is_none());
+    atlas.set_fallback(magenta.clone());
+    assert_eq!(atlas.get_corner_or_fallback(16), Some(&magenta));
+    assert_eq!(GridCornerAtlas::default().get_corner_or(mask::ISOLATED, &magenta), &magenta);
+}
+
+#[test]
+#[cfg(feature = "ktx2")]
+fn test_corner_ktx2() {
+    use tileset::CompressionFormat;
+    let atlas = wang_atlas();
+    let path = temp_dir("ktx2").join("atlas.ktx2");
+    atlas.export_ktx2(&path, CompressionFormat::Bc7).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let word = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+    let long = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap()) as usize;
+    assert_eq!(&bytes[..12], b"\xABKTX 20\xBB\r\n\x1A\n");
+    // VK_FORMAT_BC7_SRGB_BLOCK
+    assert_eq!(word(12), 146);
+    assert_eq!((word(20), word(24)), (32 * 16, 32));
+    assert_eq!(word(36), 1);
+    let (offset, length) = (long(80), long(88));
+    assert_eq!(length, 32 * 16 * 32);
+    assert_eq!(offset % 16, 0);
+    assert_eq!(bytes.len(), offset + length);
+    assert_eq!(word(word(48) as usize), word(52));
+}
+
+#[test]
+#[cfg(feature = "ora")]
+fn test_corner_ora() {
+    let atlas = wang_atlas();
+    let path = temp_dir("ora").join("atlas.ora");
+    atlas.export_ora(&path).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    // walk the local file headers, the entries are stored so the data follows the name directly
+    let mut entries = std::collections::BTreeMap::new();
+    let mut order = vec![];
+    let mut i = 0;
+    while bytes[i..i + 4] == [0x50, 0x4B, 0x03, 0x04] {
+        let half = |at: usize| u16::from_le_bytes([bytes[i + at], bytes[i + at + 1]]) as usize;
+        let size = u32::from_le_bytes(bytes[i + 22..i + 26].try_into().unwrap()) as usize;
+        let start = i + 30 + half(26) + half(28);
+        let name = String::from_utf8(bytes[i + 30..i + 30 + half(26)].to_vec()).unwrap();
+        order.push(name.clone());
+        entries.insert(name, bytes[start..start + size].to_vec());
+        i = start + size;
+    }
+    assert_eq!(order[0], "mimetype");
+    assert_eq!(entries["mimetype"], b"image/openraster");
+    let stack = String::from_utf8(entries["stack.xml"].clone()).unwrap();
+    assert_eq!(stack.matches("<layer ").count(), 16);
+    for mask in 0..16u8 {
+        let name = format!("0b{:04b}", mask);
+        assert!(stack.contains(&format!("name=\"{}\"", name)), "{}", stack);
+        let layer = image::load_from_memory(&entries[&format!("data/{}.png", name)]).unwrap().to_rgba8();
+        assert_eq!(&layer, atlas.get_corner_mask(mask));
+    }
+    let merged = image::load_from_memory(&entries["mergedimage.png"]).unwrap().to_rgba8();
+    assert_eq!(merged, atlas.to_image());
+    assert!(entries.contains_key("Thumbnails/thumbnail.png"));
+}
+
+#[test]
+fn test_corner_placeholder() {
+    let atlas = GridCornerAtlas::placeholder(8, 6, Rgba([255, 0, 255, 255])).unwrap();
+    assert_eq!(atlas.get_cell_size(), (8, 6));
+    for mask in 0..16 {
+        let cell = atlas.get_corner_mask(mask);
+        assert_eq!(cell.dimensions(), (8, 6));
+        for other in 0..mask {
+            assert_ne!(cell, atlas.get_corner_mask(other));
+        }
+    }
+    assert_eq!(atlas.get_corner_mask(mask::UPPER_LEFT).get_pixel(0, 0), &Rgba([255, 0, 255, 255]));
+    assert_ne!(atlas.get_corner_mask(mask::UPPER_LEFT).get_pixel(7, 5), &Rgba([255, 0, 255, 255]));
+    assert!(GridCornerAtlas::placeholder(1, 6, Rgba([255, 0, 255, 255])).is_err());
+}
+
+#[test]
+fn test_render_chunks_dirty() {
+    let atlas = GridCornerAtlas::placeholder(2, 2, Rgba([0, 200, 0, 255])).unwrap();
+    let mut map = TilemapRenderer::new(atlas.clone(), 33, 33, 8).unwrap();
+    assert_eq!(map.get_chunk_count(), (4, 4));
+    assert_eq!(map.render_dirty().len(), 16);
+    assert!(map.dirty_chunks().is_empty());
+    let id = |x, y| ChunkId { x, y };
+    map.set_tile(4, 4, true);
+    assert_eq!(map.dirty_chunks(), [id(0, 0)]);
+    map.render_dirty();
+    map.set_tile(4, 4, true);
+    assert!(map.dirty_chunks().is_empty());
+    // a vertex on the corner of four chunks is shared by one tile in each
+    map.set_tile(8, 8, true);
+    assert_eq!(map.dirty_chunks(), [id(0, 0), id(1, 0), id(0, 1), id(1, 1)]);
+    map.render_dirty();
+    map.set_tile(16, 3, true);
+    assert_eq!(map.dirty_chunks(), [id(1, 0), id(2, 0)]);
+    map.render_dirty();
+    map.set_tile(32, 32, true);
+    map.set_tile(0, 0, true);
+    assert_eq!(map.dirty_chunks(), [id(0, 0), id(3, 3)]);
+    let rendered = map.render_dirty();
+    assert_eq!(rendered.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [id(0, 0), id(3, 3)]);
+    // the cached chunks stitch together into the full map
+    let grid: Vec<Vec<bool>> = (0..33).map(|y| (0..33).map(|x| map.get_tile(x, y)).collect()).collect();
+    let full = render_map(&atlas, &grid);
+    for cy in 0..4 {
+        for cx in 0..4 {
+            let chunk = map.get_chunk(id(cx, cy)).unwrap();
+            assert_eq!(chunk, &full.view(cx * 16, cy * 16, 16, 16).to_image());
+        }
+    }
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn test_render_chunks_stream() {
+    use futures_util::{FutureExt, StreamExt};
+    let atlas = GridCornerAtlas::placeholder(2, 2, Rgba([0, 200, 0, 255])).unwrap();
+    let mut map = TilemapRenderer::new(atlas.clone(), 41, 25, 8).unwrap();
+    for (x, y) in [(0, 0), (8, 8), (16, 3), (20, 12), (40, 24)] {
+        map.set_tile(x, y, true);
+    }
+    let grid: Vec<Vec<bool>> = (0..25).map(|y| (0..41).map(|x| map.get_tile(x, y)).collect()).collect();
+    // every chunk is ready as soon as it is polled
+    let streamed: Vec<_> = tileset::render_chunks_stream(&atlas, &grid, 8).collect::<Vec<_>>().now_or_never().unwrap();
+    let id = |x, y| ChunkId { x, y };
+    // 5 * 3 chunks, starting from the center and ending at the corners
+    assert_eq!(streamed.len(), 15);
+    let first: Vec<_> = streamed.iter().map(|(id, _)| *id).take(5).collect();
+    assert_eq!(first, [id(2, 1), id(2, 0), id(1, 1), id(3, 1), id(2, 2)]);
+    assert_eq!(streamed.last().unwrap().0, id(4, 2));
+    let mut expected = map.render_dirty();
+    let mut streamed = streamed;
+    streamed.sort_by_key(|(id, _)| *id);
+    expected.sort_by_key(|(id, _)| *id);
+    assert_eq!(streamed, expected);
+    // a zero chunk size yields nothing, and a jagged grid is cut to its shortest row like render_map
+    assert_eq!(tileset::render_chunks_stream(&atlas, &grid, 0).count().now_or_never(), Some(0));
+    let mut jagged = grid.clone();
+    jagged[20].truncate(30);
+    let chunks: Vec<_> = tileset::render_chunks_stream(&atlas, &jagged, 8).collect::<Vec<_>>().now_or_never().unwrap();
+    let full = render_map(&atlas, &jagged);
+    assert_eq!(full.dimensions(), (29 * 2, 24 * 2));
+    assert_eq!(chunks.len(), 4 * 3);
+    for (id, chunk) in chunks {
+        let (w, h) = chunk.dimensions();
+        assert_eq!(chunk, full.view(id.x * 16, id.y * 16, w, h).to_image());
+    }
+}
+
+#[test]
+fn test_render_blue_noise() {
+    let mut sheet = RgbaImage::new(32, 6);
+    for (x, y, pixel) in sheet.enumerate_pixels_mut() {
+        if y < 2 || x >= 30 {
+            *pixel = Rgba([0, (y / 2 * 100) as u8, 0, 255]);
+        }
+    }
+    let mut atlas = GridCornerAtlas::from_standard(&sheet).unwrap();
+    assert_eq!(atlas.get_count(mask::SURROUNDED), 3);
+    let grid = vec![vec![true; 17]; 17];
+    // static variants are picked by render_map itself
+    let map = render_map(&atlas, &grid);
+    let variant = |x: u32, y: u32| map.get_pixel(x * 2, y * 2)[1] as usize / 100;
+    let mut used = [0; 3];
+    let mut repeats = 0;
+    for y in 0..16 {
+        for x in 0..16 {
+            used[variant(x, y)] += 1;
+            repeats += (x > 0 && variant(x - 1, y) == variant(x, y)) as usize;
+        }
+    }
+    // evenly spread, and fewer repeated neighbors than the 80 expected from white noise
+    assert!(used.iter().all(|n| *n > 64), "{:?}", used);
+    assert!(repeats < 80, "{}", repeats);
+    // the phase only applies to animations
+    for phase in [AnimationPhase::Lockstep, AnimationPhase::PerCell, AnimationPhase::BlueNoise] {
+        assert_eq!(render_map_at(&atlas, &grid, Duration::from_millis(300), phase), map);
+    }
+    // the chunks pick the same variants as the whole map
+    let mut chunked = TilemapRenderer::new(atlas.clone(), 17, 17, 5).unwrap();
+    for y in 0..17 {
+        for x in 0..17 {
+            chunked.set_tile(x, y, true);
+        }
+    }
+    for (id, chunk) in chunked.render_dirty() {
+        let (w, h) = chunk.dimensions();
+        assert_eq!(chunk, map.view(id.x * 10, id.y * 10, w, h).to_image());
+    }
+    // animated atlases show the same frame everywhere in lockstep
+    atlas.set_frame_duration(Duration::from_millis(100));
+    let lockstep = render_map(&atlas, &grid);
+    assert!(lockstep.pixels().all(|p| p[1] == 0));
+    assert_ne!(render_map_at(&atlas, &grid, Duration::ZERO, AnimationPhase::BlueNoise), lockstep);
+}
+
+#[test]
+fn test_as_ref_image() {
+    fn takes(image: impl AsRef<RgbaImage>) -> (u32, u32) {
+        image.as_ref().dimensions()
+    }
+    let raw = image::open(fixture("rpg4x6/grass.png")).unwrap().to_rgba8();
+    let vx = GridCornerRMVX::new(&raw, (0, 0), (raw.width() / 4, raw.height() / 6)).unwrap();
+    assert_eq!(takes(&vx), raw.dimensions());
+    assert_eq!(takes(vx), raw.dimensions());
+}
+
+#[test]
+fn test_compare_dialects() {
+    let xp = image::open(fixture("rpg6x8/grass.png")).unwrap().to_rgba8();
+    let (w, h) = xp.dimensions();
+    let wang = GridCornerRMXP::new(&xp, (0, 0), (w / 6, h / 8)).unwrap().as_standard().unwrap().to_wang();
+    let report = compare_dialects(&xp, &wang).unwrap();
+    assert_eq!(report.len(), 16);
+    assert!(report.iter().all(|(_, difference)| *difference == 0.0));
+    // swapping two cells of the wang sheet shows up in exactly those masks
+    let (cw, ch) = (wang.width() / 4, wang.height() / 4);
+    let mut swapped = wang.clone();
+    let a = wang.view(0, 0, cw, ch).to_image();
+    let b = wang.view(cw, 0, cw, ch).to_image();
+    overlay(&mut swapped, &b, 0, 0);
+    overlay(&mut swapped, &a, cw as i64, 0);
+    let changed = compare_dialects(&xp, &swapped).unwrap().into_iter().filter(|(_, d)| *d > 0.0).count();
+    assert_eq!(changed, 2);
+    assert!(compare_dialects(&xp, &RgbaImage::new(wang.width() * 2, wang.height() * 2)).is_err());
+}
+
+#[test]
+fn test_fs_insert_writes_png() {
+    let root = temp_dir("insert-png");
+    let tiles = FileSystemTiles::new(root.join("nested"), 32, 32).unwrap();
+    let atlas = wang_atlas();
+    tiles.insert_atlas("grass", TileAtlasData::GridCorner(Box::new(atlas.clone()))).unwrap();
+    let written = image::open(root.join("nested/grass.png")).unwrap().to_rgba8();
+    assert_eq!(written, atlas.to_image());
+    let json = std::fs::read_to_string(root.join("nested/TileSet.json5")).unwrap();
+    assert!(json.contains(r#""key": "grass.png""#), "{}", json);
+    let loaded = FileSystemTiles::load(root.join("nested")).unwrap();
+    assert_eq!(&loaded.get_corner("grass", true, false, true, true, 0).unwrap(), atlas.get_corner(true, false, true, true));
+    // the image must stay in the workspace
+    let outside = root.join("outside").display().to_string();
+    for name in ["../escaped", "sub/../../escaped", outside.as_str()] {
+        let data = TileAtlasData::GridCorner(Box::new(atlas.clone()));
+        assert!(tiles.validate_insert(name, &data).is_err(), "{}", name);
+        assert!(tiles.insert_atlas(name, data).is_err(), "{}", name);
+        assert!(tiles.rename("grass", name).is_err(), "{}", name);
+    }
+    assert!(!root.join("escaped.png").exists() && !root.join("outside.png").exists());
+    tiles.insert_atlas("sub/grass", TileAtlasData::GridCorner(Box::new(atlas.clone()))).unwrap();
+    assert!(root.join("nested/sub/grass.png").exists());
+}
+
+#[test]
+fn test_fs_rename() {
+    let root = temp_dir("rename");
+    let tiles = FileSystemTiles::new(&root, 8, 8).unwrap();
+    let atlas = GridCornerAtlas::placeholder(8, 8, Rgba([0, 200, 0, 255])).unwrap().with_key("unused.png");
+    assert_eq!(atlas.get_key(), "unused.png");
+    tiles.insert_atlas("grass", TileAtlasData::GridCorner(Box::new(atlas.clone()))).unwrap();
+    tiles.insert_atlas("water", TileAtlasData::GridCorner(Box::new(atlas.clone()))).unwrap();
+    assert!(tiles.rename("grass", "water").is_err());
+    assert!(tiles.rename("sand", "dune").is_err());
+    tiles.rename("grass", "meadow").unwrap();
+    assert!(tiles.get_atlas("grass", 0).is_none());
+    assert_eq!(tiles.get_atlas("meadow", 0).unwrap().get_name(), "meadow.png");
+    assert!(!root.join("grass.png").exists());
+    assert_eq!(image::open(root.join("meadow.png")).unwrap().to_rgba8(), atlas.to_image());
+    let json = std::fs::read_to_string(root.join("TileSet.json5")).unwrap();
+    assert!(json.contains(r#""key": "meadow.png""#) && !json.contains("grass"), "{}", json);
+    // an image already at the new path is never replaced
+    std::fs::write(root.join("taken.png"), b"not an atlas").unwrap();
+    assert!(tiles.rename("meadow", "taken").is_err());
+    assert!(tiles.get_atlas("taken", 0).is_none());
+    assert_eq!(std::fs::read(root.join("taken.png")).unwrap(), b"not an atlas");
+    assert_eq!(&tiles.get_corner("meadow", true, true, false, true, 0).unwrap(), atlas.get_corner(true, true, false, true));
+    let loaded = FileSystemTiles::load(&root).unwrap();
+    assert_eq!(&loaded.get_corner("meadow", true, true, false, true, 0).unwrap(), atlas.get_corner(true, true, false, true));
+}
+
+#[test]
+fn test_corner_integer_scalable() {
+    let atlas = GridCornerAtlas::placeholder(16, 16, Rgba([255, 0, 255, 255])).unwrap();
+    assert_eq!(atlas.assert_integer_scalable(48).unwrap(), 3);
+    assert_eq!(atlas.assert_integer_scalable(16).unwrap(), 1);
+    assert_eq!(atlas.assert_integer_scalable(4).unwrap(), 4);
+    assert!(atlas.assert_integer_scalable(40).is_err());
+    assert!(atlas.assert_integer_scalable(6).is_err());
+    assert!(atlas.assert_integer_scalable(0).is_err());
+    let wide = GridCornerAtlas::placeholder(16, 6, Rgba([255, 0, 255, 255])).unwrap();
+    assert_eq!(wide.assert_integer_scalable(8).unwrap(), 2);
+    assert!(wide.assert_integer_scalable(4).is_err());
+}
+
+#[test]
+fn test_corner_resized_alpha() {
+    let mut sheet = RgbaImage::new(16 * 8, 8);
+    for (x, _, pixel) in sheet.enumerate_pixels_mut() {
+        let alpha = (x % 8) as u8 * 36;
+        *pixel = if alpha == 0 { Rgba([0, 0, 0, 0]) } else { Rgba([255, 255, 255, alpha]) };
+    }
+    let atlas = GridCornerAtlas::from_standard(&sheet).unwrap();
+    let small = atlas.resized(4, 4, image::imageops::FilterType::Triangle).unwrap();
+    assert_eq!(small.get_cell_size(), (4, 4));
+    for pixel in small.get_corner_mask(mask::SURROUNDED).pixels().filter(|p| p[3] > 0) {
+        assert!(pixel[0] >= 254 && pixel[1] >= 254 && pixel[2] >= 254, "{:?}", pixel);
+    }
+    let naive = atlas.resized_with(4, 4, image::imageops::FilterType::Triangle, false).unwrap();
+    assert!(naive.get_corner_mask(mask::SURROUNDED).pixels().any(|p| p[3] > 0 && p[0] < 250));
+}
+
+#[test]
+fn test_rpg_coverage_image() {
+    let raw = image::open(fixture("rpg6x8/grass.png")).unwrap().to_rgba8();
+    let (w, h) = (raw.width() / 6, raw.height() / 8);
+    let xp = GridCornerRMXP::new(&raw, (0, 0), (w, h)).unwrap();
+    let used = xp.coverage();
+    assert_eq!(used.iter().flatten().filter(|u| !**u).count(), 24);
+    let overlay = xp.coverage_image();
+    for (x, y, pixel) in overlay.enumerate_pixels() {
+        let source = raw.get_pixel(x, y);
+        match used[(y / h) as usize][(x / w) as usize] {
+            true => assert_eq!(pixel, source),
+            false => assert_eq!(pixel, &Rgba([source[0] / 4, source[1] / 4, source[2] / 4, source[3]])),
+        }
+    }
+    let raw = image::open(fixture("rpg4x6/grass.png")).unwrap().to_rgba8();
+    let vx = GridCornerRMVX::new(&raw, (0, 0), (raw.width() / 4, raw.height() / 6)).unwrap();
+    assert!(vx.coverage().iter().flatten().all(|u| *u));
+    assert_eq!(vx.coverage_image(), raw);
+}
+
+fn make_workspace(name: &str, count: usize) -> PathBuf {
+    let root = temp_dir(name);
+    let tiles = FileSystemTiles::new(&root, 32, 32).unwrap();
+    let atlas = wang_atlas();
+    for i in 0..count {
+        tiles.insert_atlas(&format!("atlas{:03}", i), TileAtlasData::GridCorner(Box::new(atlas.clone()))).unwrap();
+    }
+    root
+}
+
+#[test]
+fn test_fs_open() {
+    let root = make_workspace("open", 3);
+    let tiles = FileSystemTiles::open(&root).unwrap();
+    let expected = image::open(root.join("atlas001.png")).unwrap().to_rgba8();
+    std::fs::remove_file(root.join("atlas001.png")).unwrap();
+    assert_eq!(*tiles.get_image("atlas001.png").unwrap(), expected);
+    let error = FileSystemTiles::open(&root).unwrap_err().to_string();
+    assert!(error.contains("atlas001.png"), "{}", error);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_fs_open_parallel() {
+    let root = make_workspace("open-parallel", 8);
+    let serial = FileSystemTiles::open(&root).unwrap();
+    let parallel = FileSystemTiles::open_parallel(&root).unwrap();
+    for i in 0..8 {
+        let key = format!("atlas{:03}.png", i);
+        assert_eq!(parallel.get_image(&key).unwrap(), serial.get_image(&key).unwrap());
+    }
+    std::fs::remove_file(root.join("atlas005.png")).unwrap();
+    std::fs::remove_file(root.join("atlas002.png")).unwrap();
+    let error = FileSystemTiles::open_parallel(&root).unwrap_err().to_string();
+    assert!(error.contains("atlas002.png"), "{}", error);
+}
+
+#[test]
+fn test_fs_warmup() {
+    let root = make_workspace("warmup", 3);
+    let tiles = FileSystemTiles::load(&root).unwrap();
+    assert!(!tiles.is_decoded("atlas001.png"));
+    let expected = tiles.get_image("atlas001.png").unwrap();
+    tiles.warmup().unwrap();
+    assert!((0..3).all(|i| tiles.is_decoded(&format!("atlas{:03}.png", i))));
+    std::fs::remove_file(root.join("atlas001.png")).unwrap();
+    assert_eq!(tiles.get_image("atlas001.png").unwrap(), expected);
+    // the decoded sheet is shared, getting corners does not copy it
+    let sheet = tiles.get_image("atlas001.png").unwrap();
+    assert!(Arc::ptr_eq(&sheet, &tiles.get_image("atlas001.png").unwrap()));
+    // every cell is cropped up front, getting corners takes them from memory
+    assert!((0..16).all(|mask| tiles.is_cell_cached("atlas001", mask, 0)));
+    let corner = tiles.get_corner("atlas001", true, true, false, true, 0).unwrap();
+    assert_eq!(&corner, wang_atlas().get_corner_mask(0b1011));
+    assert!(!tiles.is_cell_cached("atlas001", 0b1011, 1));
+    // replacing the atlas drops its cells
+    assert!(tiles.is_cell_cached("atlas002", 0b1011, 0));
+    tiles
+        .insert_atlas("atlas002", TileAtlasData::GridCornerWang(Box::new(GridCornerWang::new("atlas002.png", 32, 32))))
+        .unwrap();
+    assert!(!tiles.is_cell_cached("atlas002", 0b1011, 0));
+    let error = FileSystemTiles::load(&root).unwrap().warmup().unwrap_err().to_string();
+    assert!(error.contains("atlas001.png"), "{}", error);
+}
+
+/// Run with `cargo test --release --features rayon -- --ignored --nocapture bench_fs_open`.
+#[test]
+#[ignore]
+#[cfg(feature = "rayon")]
+fn bench_fs_open() {
+    let root = make_workspace("open-bench", 400);
+    let start = std::time::Instant::now();
+    FileSystemTiles::open(&root).unwrap();
+    let serial = start.elapsed();
+    let start = std::time::Instant::now();
+    FileSystemTiles::open_parallel(&root).unwrap();
+    let parallel = start.elapsed();
+    println!("open: {:?}, open_parallel: {:?}", serial, parallel);
+}
+
+#[test]
+fn test_corner_wang_gutter() {
+    let raw = wang_sheet();
+    let mut guttered = RgbaImage::from_pixel(32 * 4 + 3, 32 * 4 + 3, Rgba([255, 0, 255, 255]));
+    for row in 0..4 {
+        for col in 0..4 {
+            let cell = raw.view(col * 32, row * 32, 32, 32).to_image();
+            overlay(&mut guttered, &cell, (col * 33) as i64, (row * 33) as i64);
+        }
+    }
+    let atlas = GridCornerAtlas::from_wang_gutter(&guttered, 1).unwrap();
+    assert_eq!(atlas.get_cell_size(), (32, 32));
+    assert_eq!(atlas.to_wang(), raw);
+    assert_eq!(GridCornerAtlas::from_wang_gutter(&raw, 0).unwrap(), GridCornerAtlas::from_wang(&raw).unwrap());
+    assert!(GridCornerAtlas::from_wang_gutter(&guttered, 2).is_err());
+    assert!(GridCornerAtlas::from_wang_gutter(&raw, 100).is_err());
+}
+
+#[test]
+fn test_corner_authored_masks() {
+    let mut sheet = RgbaImage::new(16 * 4, 4);
+    for (x, _, pixel) in sheet.enumerate_pixels_mut() {
+        if !matches!(x / 4, 3 | 6 | 9 | 12) && x % 4 == 1 {
+            *pixel = Rgba([10, 20, 30, 40]);
+        }
+    }
+    let atlas = GridCornerAtlas::from_standard(&sheet).unwrap();
+    let authored = atlas.authored_masks();
+    assert_eq!(authored.count_ones(), 12);
+    assert_eq!(authored, !(1 << 3 | 1 << 6 | 1 << 9 | 1 << 12));
+    assert_eq!(GridCornerAtlas::default().authored_masks(), 0);
+}
+
+#[test]
+#[cfg(feature = "tiled")]
+fn test_corner_tiled_wangset() {
+    let map = tiled::Loader::new().load_tmx_map(fixture("tiled/corner.tmx")).unwrap();
+    let atlas = GridCornerAtlas::from_tiled_wangset(&map, "grass").unwrap();
+    assert_eq!(atlas, wang_atlas());
+    assert!(GridCornerAtlas::from_tiled_wangset(&map, "road").is_err());
+    assert!(GridCornerAtlas::from_tiled_wangset(&map, "water").is_err());
+}
+
+#[test]
+fn test_corner_dominant_colors() {
+    let grass = Rgba([60, 160, 40, 255]);
+    let water = Rgba([20, 53, 13, 255]);
+    let mut wang = GridCornerAtlas::placeholder(16, 16, grass).unwrap().to_wang();
+    // transparent holes and a few stray pixels must not change the result
+    for (x, y, pixel) in wang.enumerate_pixels_mut() {
+        match (x + y) % 5 {
+            0 => *pixel = Rgba([0, 0, 0, 0]),
+            1 if x % 3 == 0 => *pixel = Rgba([255, 255, 255, 255]),
+            _ => {}
+        }
+    }
+    let atlas = GridCornerAtlas::from_wang(&wang).unwrap();
+    assert_eq!(atlas.dominant_colors(), [grass, water]);
+    let empty = GridCornerAtlas::from_wang(&RgbaImage::new(64, 64)).unwrap();
+    assert_eq!(empty.dominant_colors(), [Rgba([0, 0, 0, 0]); 2]);
+}
+
+#[test]
+fn test_rpg_out_of_range() {
+    let vx = GridCornerRMVX::load(fixture("rpg4x6/grass.png")).unwrap();
+    let standard = vx.as_standard().unwrap();
+    for mask in 0..16 {
+        assert_eq!(&vx.view_corner(mask).unwrap(), standard.get_corner_mask(mask));
+    }
+    assert!(vx.view_corner(16).is_err());
+    assert!(vx.view_corner(u8::MAX).is_err());
+    // cells without a source are left empty instead of panicking
+    let (w, h) = vx.as_ref().dimensions();
+    assert_eq!(vx.as_complete().as_ref().dimensions(), (w / 4 * 24, h / 6 * 8));
+    let xp = GridCornerRMXP::load(fixture("rpg6x8/grass.png")).unwrap();
+    let (w, h) = xp.as_ref().dimensions();
+    assert_eq!(xp.as_complete().as_ref().dimensions(), (w / 6 * 24, h / 8 * 8));
+}
+
+#[test]
+fn test_corner_collision() {
+    let mut atlas = GridCornerAtlas::placeholder(8, 8, Rgba([0, 0, 0, 255])).unwrap();
+    atlas.collision_from_alpha();
+    assert_eq!(atlas.collision_for(0b1111), 0b1111);
+    let mut cells = atlas.to_image();
+    // clear the exterior quadrants, so only the covered corners are solid
+    for (x, y, pixel) in cells.enumerate_pixels_mut() {
+        let mask = x / 8;
+        let bit = (x % 8 >= 4) as u32 | ((y % 8 >= 4) as u32) << 1;
+        if mask >> bit & 1 == 0 {
+            pixel[3] = 0;
+        }
+    }
+    let mut atlas = GridCornerAtlas::from_standard(&cells).unwrap();
+    atlas.collision_from_alpha();
+    for mask in 0..16 {
+        assert_eq!(atlas.collision_for(mask), mask);
+    }
+    atlas.set_collision(0b0000, 0b0110);
+    let json = serde_json::to_string(&atlas).unwrap();
+    let back: GridCornerAtlas = serde_json::from_str(&json).unwrap();
+    for mask in 0..16 {
+        assert_eq!(back.collision_for(mask), atlas.collision_for(mask));
+    }
+    assert_eq!(back.collision_for(0b0000), 0b0110);
+    assert!(std::panic::catch_unwind(|| back.collision_for(16)).is_err());
+    assert!(std::panic::catch_unwind(|| back.get_mask_opacity(16)).is_err());
+    assert!(back.tags_for(16).is_empty());
+}
+
+#[test]
+fn test_corner_diagonal() {
+    let mut atlas = GridCornerAtlas::placeholder(4, 4, Rgba([0, 200, 0, 255])).unwrap();
+    assert!(atlas.get_diagonal(true, false, false, true).is_none());
+    let mut strip = RgbaImage::new(4 * 16, 4);
+    // a bridge from the north east to the south west, the diagonal mask has the ne and sw bits set
+    let bridge = grid_corner_mask(false, true, true, false);
+    for (x, y, pixel) in strip.enumerate_pixels_mut() {
+        if x / 4 == bridge as u32 && (x % 4 + y) == 3 {
+            *pixel = Rgba([120, 80, 40, 255]);
+        }
+    }
+    assert!(atlas.set_diagonal_strip(&RgbaImage::new(4 * 15, 4)).is_err());
+    atlas.set_diagonal_strip(&strip).unwrap();
+    let cell = atlas.get_diagonal(true, false, false, true).unwrap();
+    assert_eq!(cell.get_pixel(3, 0), &Rgba([120, 80, 40, 255]));
+    assert!((0..16).all(|mask| atlas.get_corner_mask(mask) != cell));
+    assert!(atlas.get_diagonal(false, true, true, false).is_none());
+    assert!(atlas.get_diagonal(false, false, false, false).is_none());
+}
+
+#[test]
+fn test_corner_validate_all() {
+    let raw = wang_sheet();
+    assert!(GridCornerAtlas::validate_all(&raw).is_ok());
+    // one pixel too wide, and the interior cell at column 2 row 1 erased
+    let mut broken = RgbaImage::new(129, 128);
+    image::imageops::replace(&mut broken, &raw, 0, 0);
+    for y in 32..64 {
+        for x in 64..96 {
+            broken.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+        }
+    }
+    let problems = GridCornerAtlas::validate_all(&broken).unwrap_err();
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems[0].to_string().contains("width 129"), "{}", problems[0]);
+    assert!(problems[1].to_string().contains("0b1111"), "{}", problems[1]);
+    assert_eq!(GridCornerAtlas::validate_all(&RgbaImage::new(2, 0)).unwrap_err().len(), 2);
+}
+
+#[test]
+fn test_corner_export_indexed() {
+    let green = Rgba([0, 200, 0, 255]);
+    let atlas = GridCornerAtlas::placeholder(4, 4, green).unwrap();
+    let shade = Rgba([0, 66, 0, 255]);
+    let path = temp_dir("indexed").join("atlas.png");
+    assert!(atlas.export_indexed(&path, &[green], false).is_err());
+    atlas.export_indexed(&path, &[Rgba([0, 0, 0, 0]), shade, green], false).unwrap();
+    let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+    let reader = decoder.read_info().unwrap();
+    assert_eq!(reader.info().color_type, png::ColorType::Indexed);
+    assert_eq!(reader.info().bit_depth, png::BitDepth::Eight);
+    assert_eq!(image::open(&path).unwrap().to_rgba8(), atlas.to_image());
+    // the shade snaps to the closer of the two palette entries
+    atlas.export_indexed(&path, &[Rgba([0, 60, 0, 255]), green], true).unwrap();
+    let snapped = image::open(&path).unwrap().to_rgba8();
+    assert_eq!(snapped.get_pixel(0, 0), &Rgba([0, 60, 0, 255]));
+    assert_eq!(snapped.get_pixel(15 * 4, 0), &green);
+}
+
+#[test]
+fn test_corner_builder() {
+    let placeholder = GridCornerAtlas::placeholder(6, 4, Rgba([0, 200, 0, 255])).unwrap();
+    let mut builder = GridCornerAtlas::builder();
+    for mask in 0..16 {
+        builder.mask(mask, placeholder.get_corner_mask(mask).clone());
+    }
+    let atlas = builder.key("built.png").build().unwrap();
+    assert_eq!(atlas.get_key(), "built.png");
+    assert_eq!(atlas.get_cell_size(), (6, 4));
+    assert_eq!(atlas.to_image(), placeholder.to_image());
+    let frame = RgbaImage::from_pixel(6, 4, Rgba([0, 0, 255, 255]));
+    let animated = builder.mask(0b1111, frame.clone()).count(0b1111, 3).build().unwrap();
+    assert_eq!(animated.get_count(0b1111), 3);
+    assert_eq!(animated.get_corner_variant(0b1111, 1), &frame);
+    assert_eq!(animated.get_corner_variant(0b1111, 2), &RgbaImage::new(6, 4));
+    assert!(builder.count(0b1111, 1).build().is_err());
+    // missing masks are transparent
+    let sparse = GridCornerAtlas::builder().mask(0b0110, frame.clone()).build().unwrap();
+    assert_eq!(sparse.get_corner_mask(0b0110), &frame);
+    assert_eq!(sparse.authored_masks(), 1 << 0b0110);
+    assert!(GridCornerAtlas::builder().mask(1, frame).mask(2, RgbaImage::new(4, 4)).build().is_err());
+    assert!(GridCornerAtlas::builder().build().is_err());
+}
+
+#[test]
+fn test_animation_cursor() {
+    let frames = (0..4).map(|i| RgbaImage::from_pixel(2, 2, Rgba([i * 60, 0, 0, 255]))).collect();
+    let mut animation = AnimationFrame::from_frames("water.png", frames).unwrap();
+    assert_eq!(animation.cursor().advance(Duration::from_secs(3)), 0);
+    animation.set_frame_duration(Duration::from_millis(100));
+    assert_eq!(animation.frame_index_at(Duration::from_millis(399)), 3);
+    assert_eq!(animation.frame_at(Duration::from_millis(420)).unwrap().get_pixel(0, 0)[0], 0);
+    let mut cursor = animation.cursor();
+    assert_eq!(cursor.advance(Duration::from_millis(50)), 0);
+    assert_eq!(cursor.advance(Duration::from_millis(100)), 1);
+    assert_eq!(cursor.advance(Duration::from_millis(200)), 3);
+    // past the end of the loop back to an early frame
+    assert_eq!(cursor.advance(Duration::from_millis(150)), 1);
+    assert_eq!(cursor.elapsed(), Duration::from_millis(100));
+    // a step of many loops lands where the remainder does
+    assert_eq!(cursor.advance(Duration::from_millis(400 * 1000 + 250)), 3);
+    let mut time = Duration::ZERO;
+    let mut cursor = animation.cursor();
+    for _ in 0..1000 {
+        let dt = Duration::from_micros(16_667);
+        time += dt;
+        assert_eq!(cursor.advance(dt), animation.frame_index_at(time));
+    }
+}
+
+#[test]
+fn test_fs_attribution() {
+    let root = temp_dir("attribution");
+    let mut tiles = FileSystemTiles::new(&root, 8, 8).unwrap();
+    let plain = GridCornerAtlas::placeholder(8, 8, Rgba([0, 200, 0, 255])).unwrap();
+    let mut atlas = plain.clone();
+    atlas.set_attribution(Some("Grass by Jane Doe"));
+    atlas.set_license(Some("CC-BY-4.0"));
+    tiles.insert_atlas("grass", TileAtlasData::GridCorner(Box::new(atlas))).unwrap();
+    tiles.insert_atlas("plain", TileAtlasData::GridCorner(Box::new(plain))).unwrap();
+    let loaded = FileSystemTiles::load(&root).unwrap();
+    assert_eq!((loaded.get_attribution(), loaded.get_license()), (None, None));
+    tiles.set_attribution(Some("Tiles by the Example Team")).unwrap();
+    tiles.set_license(Some("CC0-1.0")).unwrap();
+    let loaded = FileSystemTiles::load(&root).unwrap();
+    assert_eq!(loaded.get_attribution(), Some("Tiles by the Example Team"));
+    assert_eq!(loaded.get_license(), Some("CC0-1.0"));
+    let Some(TileAtlasData::GridCorner(grass)) = loaded.get_atlas("grass", 0)
+    else {
+        panic!("grass is not a corner atlas")
+    };
+    assert_eq!((grass.get_attribution(), grass.get_license()), (Some("Grass by Jane Doe"), Some("CC-BY-4.0")));
+    let Some(TileAtlasData::GridCorner(plain)) = loaded.get_atlas("plain", 0)
+    else {
+        panic!("plain is not a corner atlas")
+    };
+    assert_eq!((plain.get_attribution(), plain.get_license()), (None, None));
+    tiles.set_license(None).unwrap();
+    let json = std::fs::read_to_string(root.join("TileSet.json5")).unwrap();
+    assert!(!json.contains("CC0-1.0") && json.contains("CC-BY-4.0"), "{}", json);
+}
+
+#[test]
+fn test_corner_sdf() {
+    let disk = RgbaImage::from_fn(16, 16, |x, y| {
+        let (dx, dy) = (x as f64 - 7.5, y as f64 - 7.5);
+        if dx * dx + dy * dy < 25.0 {
+            Rgba([255, 255, 255, 255])
+        }
+        else {
+            Rgba([0, 0, 0, 0])
+        }
+    });
+    let mut builder = GridCornerAtlas::builder();
+    builder.mask(0b0000, disk).mask(0b1111, RgbaImage::from_pixel(16, 16, Rgba([255, 255, 255, 255])));
+    let atlas = builder.build().unwrap();
+    let sdf = atlas.to_sdf_atlas(4);
+    assert_eq!(sdf.dimensions(), (16 * 16, 16));
+    // inside the disk is positive, growing towards the center, outside is negative
+    assert!(sdf.get_pixel(8, 8)[0] > sdf.get_pixel(11, 8)[0]);
+    assert!(sdf.get_pixel(11, 8)[0] > 128);
+    assert!(sdf.get_pixel(14, 8)[0] < 128);
+    assert_eq!(sdf.get_pixel(0, 0)[0], 1);
+    // the opaque cell next to the transparent ones stays fully inside
+    assert!((0..16).all(|x| (0..16).all(|y| sdf.get_pixel(15 * 16 + x, y)[0] == 255)));
+    assert!(sdf.get_pixel(14 * 16 + 15, 8)[0] < 128);
+}
+
+/// Counts up, enough to walk through the variants deterministically.
+struct StepRng(u32);
+
+impl rand_core::RngCore for StepRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0 = self.0.wrapping_add(1);
+        self.0
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.next_u32() as u64
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.fill(self.next_u32() as u8)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        Ok(self.fill_bytes(dest))
+    }
+}
+
+#[test]
+fn test_corner_empty_variants() {
+    // the cells are not serialized, so a deserialized atlas has counts but no cells
+    let json = r#"{"key": "water.png", "cell_w": 2, "cell_h": 2, "count": [3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3]}"#;
+    let mut atlas: GridCornerAtlas = serde_json::from_str(json).unwrap();
+    let grid = vec![vec![true, false, true]; 3];
+    let mut rng = StepRng(0);
+    assert!(atlas.select_variant(0b0101, 7).is_none());
+    assert!(atlas.get_corner_random(true, false, true, false, &mut rng).is_none());
+    assert!(atlas.get_corner_or_fallback(0b0101).is_none());
+    assert_eq!(render_map(&atlas, &grid), RgbaImage::new(4, 4));
+    let magenta = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 255, 255]));
+    atlas.set_fallback(magenta.clone());
+    assert_eq!(atlas.select_variant(0b0101, 7), Some(&magenta));
+    assert_eq!(atlas.get_corner_random(true, false, true, false, &mut rng), Some(&magenta));
+    assert_eq!(atlas.get_corner_or_fallback(0b0101), Some(&magenta));
+    let filled = RgbaImage::from_pixel(4, 4, Rgba([255, 0, 255, 255]));
+    for phase in [AnimationPhase::Lockstep, AnimationPhase::PerCell, AnimationPhase::BlueNoise] {
+        assert_eq!(render_map_at(&atlas, &grid, Duration::from_millis(250), phase), filled);
+    }
+    let mut map = TilemapRenderer::new(atlas, 3, 3, 1).unwrap();
+    assert!(map.render_dirty().iter().all(|(_, chunk)| chunk == &magenta));
+    // loaded atlases wrap the index around the variants
+    let mut builder = GridCornerAtlas::builder();
+    let frames: Vec<_> = (0..3).map(|i| RgbaImage::from_pixel(2, 2, Rgba([i * 80, 0, 0, 255]))).collect();
+    for frame in &frames {
+        builder.mask(0b1111, frame.clone());
+    }
+    let atlas = builder.build().unwrap();
+    assert_eq!(atlas.select_variant(0b1111, 4), Some(&frames[1]));
+    let picked: Vec<_> = (0..3).map(|_| atlas.get_corner_random(true, true, true, true, &mut rng).unwrap()).collect();
+    assert!(frames.iter().all(|f| picked.contains(&f)));
+}
+
+#[test]
+fn test_corner_trimmed() {
+    let atlas = GridCornerAtlas::placeholder(8, 8, Rgba([0, 200, 0, 255])).unwrap();
+    let mut builder = GridCornerAtlas::builder();
+    for mask in 0..16 {
+        // keep only the covered quadrants, so each cell has its own margins
+        let mut cell = atlas.get_corner_mask(mask).clone();
+        for (x, y, pixel) in cell.enumerate_pixels_mut() {
+            let bit = (x >= 4) as u8 | ((y >= 4) as u8) << 1;
+            if mask >> bit & 1 == 0 {
+                *pixel = Rgba([0; 4]);
+            }
+        }
+        builder.mask(mask, cell);
+    }
+    let decorated = builder.build().unwrap();
+    let trimmed = decorated.to_trimmed((0.5, 1.0));
+    assert_eq!(trimmed.frames.len(), 16);
+    assert_eq!(trimmed.image.dimensions(), (4 * 24, 8));
+    let flower = trimmed.frames[0b1000];
+    assert_eq!((flower.rect.2, flower.rect.3, flower.offset), (4, 4, (4, 4)));
+    assert_eq!(flower.trimmed_pivot, (0.0, 1.0));
+    assert_eq!(trimmed.frames[0].rect.2, 0);
+    for frame in &trimmed.frames {
+        assert_eq!(&trimmed.untrim(frame), decorated.get_corner_variant(frame.mask, frame.index));
+        let (x, y, w, h) = frame.rect;
+        if w == 0 {
+            continue;
+        }
+        // the pivot in the trimmed rect lands on the pivot of the full cell
+        let px = frame.offset.0 as f32 + frame.trimmed_pivot.0 * w as f32;
+        let py = frame.offset.1 as f32 + frame.trimmed_pivot.1 * h as f32;
+        assert_eq!((px, py), (frame.pivot.0 * 8.0, frame.pivot.1 * 8.0));
+        assert!(x + w <= trimmed.image.width() && y + h <= trimmed.image.height());
+    }
+}